@@ -0,0 +1,177 @@
+use std::str::FromStr;
+
+use derive_more::derive::{Display, Error};
+
+use crate::BinaryImage;
+
+/// Characters for set and clear pixels in ASCII art.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AsciiFormat {
+    pub set: char,
+    pub clear: char,
+}
+
+impl Default for AsciiFormat {
+    fn default() -> Self {
+        Self {
+            set: '#',
+            clear: '.',
+        }
+    }
+}
+
+/// An error raised while parsing ASCII art.
+#[derive(Error, Display, Debug, Clone, PartialEq, Eq)]
+pub enum AsciiError {
+    /// A row is longer or shorter than the first one.
+    #[display("Row {row} has {found} pixels, expected {expected}.")]
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+
+    /// A character is neither the set nor the clear one.
+    #[display("Unexpected character {character:?} in row {row}, column {column}.")]
+    InvalidCharacter {
+        row: usize,
+        column: usize,
+        character: char,
+    },
+}
+
+impl BinaryImage {
+    /// Parses ASCII art, one line per row.
+    ///
+    /// Blank lines and whitespace around each line are skipped, so the art
+    /// can be indented in a raw string, unless whitespace is one of the
+    /// pixel characters.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rows differ in length or contain other
+    /// characters.
+    pub fn from_ascii(text: &str, format: &AsciiFormat) -> Result<Self, AsciiError> {
+        let trim = !format.set.is_whitespace() && !format.clear.is_whitespace();
+        let rows: Vec<&str> = text
+            .lines()
+            .map(|line| {
+                if trim {
+                    line.trim()
+                } else {
+                    line.trim_end_matches('\r')
+                }
+            })
+            .filter(|line| !trim || !line.is_empty())
+            .collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut buffer = bit_vec::BitVec::with_capacity(width * rows.len());
+        for (row, line) in rows.iter().enumerate() {
+            let found = line.chars().count();
+            if found != width {
+                return Err(AsciiError::RaggedRow {
+                    row,
+                    expected: width,
+                    found,
+                });
+            }
+            for (column, character) in line.chars().enumerate() {
+                if character == format.set {
+                    buffer.push(true);
+                } else if character == format.clear {
+                    buffer.push(false);
+                } else {
+                    return Err(AsciiError::InvalidCharacter {
+                        row,
+                        column,
+                        character,
+                    });
+                }
+            }
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(Self::from_bitvec(width as u32, rows.len() as u32, buffer))
+    }
+
+    /// Writes the image as ASCII art, one line per row, each ending with a
+    /// newline.
+    #[must_use]
+    pub fn to_ascii(&self, format: &AsciiFormat) -> String {
+        let mut text = String::with_capacity((self.width as usize + 1) * self.height as usize);
+        for y in 0..self.height {
+            let offset = self.row_offset(y);
+            for x in 0..self.width as usize {
+                text.push(if self.buffer[offset + x] {
+                    format.set
+                } else {
+                    format.clear
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Parses ASCII art of `#` and `.`, see [`BinaryImage::from_ascii`].
+impl FromStr for BinaryImage {
+    type Err = AsciiError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::from_ascii(text, &AsciiFormat::default())
+    }
+}
+
+/// Checks the rows of [`binary_image!`](crate::binary_image) at compile time.
+#[doc(hidden)]
+pub const fn validate_rows(rows: &[&str]) {
+    let mut row = 0;
+    while row < rows.len() {
+        let bytes = rows[row].as_bytes();
+        assert!(
+            bytes.len() == rows[0].len(),
+            "binary_image!: rows differ in length"
+        );
+        let mut column = 0;
+        while column < bytes.len() {
+            assert!(
+                bytes[column] == b'#' || bytes[column] == b'.',
+                "binary_image!: rows may only contain '#' and '.'"
+            );
+            column += 1;
+        }
+        row += 1;
+    }
+}
+
+/// Builds a [`BinaryImage`] from rows of `#` for set and `.` for clear
+/// pixels, checking at compile time that all rows have the same length.
+///
+/// ```
+/// let image = binary_image::binary_image![
+///     "#..",
+///     ".#.",
+/// ];
+/// assert_eq!((image.width(), image.height()), (3, 2));
+/// assert!(*image.get_pixel(1, 1));
+/// ```
+///
+/// Ragged rows do not compile:
+///
+/// ```compile_fail
+/// let image = binary_image::binary_image!["#..", "#."];
+/// ```
+#[macro_export]
+macro_rules! binary_image {
+    ($($row:literal),* $(,)?) => {{
+        const ROWS: &[&str] = &[$($row),*];
+        const _: () = $crate::__validate_ascii_rows(ROWS);
+        match $crate::BinaryImage::from_ascii(
+            &ROWS.join("\n"),
+            &$crate::AsciiFormat::default(),
+        ) {
+            Ok(image) => image,
+            Err(error) => unreachable!("{error}"),
+        }
+    }};
+}
@@ -0,0 +1,293 @@
+//! Word-level helpers over the storage of a [`BitVec`].
+//!
+//! `BitVec<u32>` keeps bit `i` in block `i / 32` at position `i % 32`, so a run
+//! of bits can be moved 32 at a time with two shifts instead of one bit per call.
+#![allow(clippy::cast_possible_truncation)]
+
+use bit_vec::BitVec;
+
+pub(crate) const WORD: usize = u32::BITS as usize;
+
+/// Mask with the lowest `len` bits set, `len <= 32`.
+#[inline]
+pub(crate) fn low_mask(len: usize) -> u32 {
+    if len >= WORD {
+        u32::MAX
+    } else {
+        (1 << len) - 1
+    }
+}
+
+/// Reads up to 32 bits starting at `offset`; the bit at `offset` ends up in bit 0.
+#[inline]
+pub(crate) fn read(buffer: &BitVec, offset: usize, len: usize) -> u32 {
+    debug_assert!(len <= WORD && offset + len <= buffer.len());
+    if len == 0 {
+        return 0;
+    }
+    let storage = buffer.storage();
+    let (block, shift) = (offset / WORD, offset % WORD);
+    let mut word = storage[block] >> shift;
+    if shift != 0 && shift + len > WORD {
+        word |= storage[block + 1] << (WORD - shift);
+    }
+    word & low_mask(len)
+}
+
+/// Writes the lowest `len` bits of `word` starting at `offset`.
+#[inline]
+pub(crate) fn write(buffer: &mut BitVec, offset: usize, len: usize, word: u32) {
+    debug_assert!(len <= WORD && offset + len <= buffer.len());
+    if len == 0 {
+        return;
+    }
+    let word = word & low_mask(len);
+    let (block, shift) = (offset / WORD, offset % WORD);
+    // SAFETY: only bits below `buffer.len()` are touched, so the unused tail of
+    // the last block stays zeroed as `BitVec` requires.
+    let storage = unsafe { buffer.storage_mut() };
+    let mask = low_mask(len) << shift;
+    storage[block] = (storage[block] & !mask) | (word << shift);
+    if shift != 0 && shift + len > WORD {
+        let rest = shift + len - WORD;
+        let mask = low_mask(rest);
+        storage[block + 1] = (storage[block + 1] & !mask) | (word >> (WORD - shift));
+    }
+}
+
+/// Copies `len` bits from `src[src_offset..]` into `dst[dst_offset..]`.
+///
+/// Both ranges may belong to different buffers; for overlapping copies inside
+/// one buffer use [`copy_within`].
+pub(crate) fn copy(
+    src: &BitVec,
+    src_offset: usize,
+    dst: &mut BitVec,
+    dst_offset: usize,
+    len: usize,
+) {
+    let mut done = 0;
+    while done < len {
+        let step = (len - done).min(WORD);
+        let word = read(src, src_offset + done, step);
+        write(dst, dst_offset + done, step, word);
+        done += step;
+    }
+}
+
+/// Copies `len` bits from `src_offset` to `dst_offset` inside one buffer,
+/// handling overlap like [`slice::copy_within`].
+pub(crate) fn copy_within(buffer: &mut BitVec, src_offset: usize, dst_offset: usize, len: usize) {
+    if src_offset == dst_offset || len == 0 {
+        return;
+    }
+    if dst_offset < src_offset {
+        let mut done = 0;
+        while done < len {
+            let step = (len - done).min(WORD);
+            let word = read(buffer, src_offset + done, step);
+            write(buffer, dst_offset + done, step, word);
+            done += step;
+        }
+    } else {
+        let mut left = len;
+        while left > 0 {
+            let step = left.min(WORD);
+            left -= step;
+            let word = read(buffer, src_offset + left, step);
+            write(buffer, dst_offset + left, step, word);
+        }
+    }
+}
+
+/// Sets `len` bits starting at `offset` to `value`.
+pub(crate) fn fill(buffer: &mut BitVec, offset: usize, len: usize, value: bool) {
+    let word = if value { u32::MAX } else { 0 };
+    let mut done = 0;
+    while done < len {
+        let step = (len - done).min(WORD);
+        write(buffer, offset + done, step, word);
+        done += step;
+    }
+}
+
+/// Returns the storage blocks holding the first `len` bits, with any bits
+/// past `len` cleared.
+pub(crate) fn blocks(buffer: &BitVec, len: usize) -> impl Iterator<Item = u32> + '_ {
+    let count = len.div_ceil(WORD);
+    buffer.storage()[..count]
+        .iter()
+        .enumerate()
+        .map(move |(i, &block)| {
+            if i + 1 == count {
+                block & low_mask(len - i * WORD)
+            } else {
+                block
+            }
+        })
+}
+
+/// Returns the position of the first set bit in `len` bits starting at `offset`,
+/// relative to `offset`.
+pub(crate) fn first_one(buffer: &BitVec, offset: usize, len: usize) -> Option<usize> {
+    let mut done = 0;
+    while done < len {
+        let step = (len - done).min(WORD);
+        let word = read(buffer, offset + done, step);
+        if word != 0 {
+            return Some(done + word.trailing_zeros() as usize);
+        }
+        done += step;
+    }
+    None
+}
+
+/// Returns the position of the last set bit in `len` bits starting at `offset`,
+/// relative to `offset`.
+pub(crate) fn last_one(buffer: &BitVec, offset: usize, len: usize) -> Option<usize> {
+    let mut left = len;
+    while left > 0 {
+        let step = left.min(WORD);
+        left -= step;
+        let word = read(buffer, offset + left, step);
+        if word != 0 {
+            return Some(left + WORD - 1 - word.leading_zeros() as usize);
+        }
+    }
+    None
+}
+
+/// Calls `f(start, end)` for each run of set bits in `len` bits starting at
+/// `offset`, with half-open bounds relative to `offset`.
+pub(crate) fn for_each_run(
+    buffer: &BitVec,
+    offset: usize,
+    len: usize,
+    mut f: impl FnMut(usize, usize),
+) {
+    let mut start = None;
+    let mut done = 0;
+    while done < len {
+        let step = (len - done).min(WORD);
+        let word = read(buffer, offset + done, step);
+        let mut bit = 0;
+        while bit < step {
+            // Look for the end of the current run or the start of the next.
+            let rest = match start {
+                Some(_) => (!word >> bit) & low_mask(step - bit),
+                None => word >> bit,
+            };
+            if rest == 0 {
+                break;
+            }
+            bit += rest.trailing_zeros() as usize;
+            match start.take() {
+                Some(first) => f(first, done + bit),
+                None => start = Some(done + bit),
+            }
+        }
+        done += step;
+    }
+    if let Some(first) = start {
+        f(first, len);
+    }
+}
+
+/// Copies `len` bits from `src[src_offset..]` into `dst[dst_offset..]` in
+/// reverse order, so the last source bit becomes the first destination bit.
+pub(crate) fn copy_reversed(
+    src: &BitVec,
+    src_offset: usize,
+    dst: &mut BitVec,
+    dst_offset: usize,
+    len: usize,
+) {
+    let mut done = 0;
+    while done < len {
+        let step = (len - done).min(WORD);
+        let word = read(src, src_offset + len - done - step, step);
+        write(
+            dst,
+            dst_offset + done,
+            step,
+            word.reverse_bits() >> (WORD - step),
+        );
+        done += step;
+    }
+}
+
+/// Transposes a 32x32 bit matrix in place, where bit `c` of `rows[r]` is the
+/// element at row `r` and column `c`.
+pub(crate) fn transpose32(rows: &mut [u32; WORD]) {
+    let mut j = WORD / 2;
+    let mut mask: u32 = 0x0000_FFFF;
+    while j != 0 {
+        let mut k = 0;
+        while k < WORD {
+            let t = ((rows[k] >> j) ^ rows[k + j]) & mask;
+            rows[k] ^= t << j;
+            rows[k + j] ^= t;
+            k = (k + j + 1) & !j;
+        }
+        j >>= 1;
+        mask ^= mask << j;
+    }
+}
+
+/// Reads `len` bits starting at `offset` into `bytes`, eight pixels per byte.
+///
+/// With `msb_first` the first pixel lands in the most significant bit of each
+/// byte, otherwise in the least significant one. Bits past `len` are zero
+/// whether or not `invert` is set.
+pub(crate) fn pack(
+    buffer: &BitVec,
+    offset: usize,
+    len: usize,
+    bytes: &mut [u8],
+    msb_first: bool,
+    invert: bool,
+) {
+    for (i, chunk) in bytes.chunks_mut(4).enumerate() {
+        let start = i * WORD;
+        let step = len.saturating_sub(start).min(WORD);
+        let mut word = read(buffer, offset + start, step);
+        if invert {
+            word = !word & low_mask(step);
+        }
+        for (byte, value) in chunk.iter_mut().zip(word.to_le_bytes()) {
+            *byte = if msb_first {
+                value.reverse_bits()
+            } else {
+                value
+            };
+        }
+    }
+}
+
+/// Writes `len` bits starting at `offset` from `bytes`, the inverse of [`pack`].
+pub(crate) fn unpack(
+    bytes: &[u8],
+    buffer: &mut BitVec,
+    offset: usize,
+    len: usize,
+    msb_first: bool,
+    invert: bool,
+) {
+    let mut done = 0;
+    for chunk in bytes.chunks(4) {
+        if done >= len {
+            break;
+        }
+        let mut le = [0; 4];
+        for (value, &byte) in le.iter_mut().zip(chunk) {
+            *value = if msb_first { byte.reverse_bits() } else { byte };
+        }
+        let mut word = u32::from_le_bytes(le);
+        if invert {
+            word = !word;
+        }
+        let step = (len - done).min(chunk.len() * 8);
+        write(buffer, offset + done, step, word);
+        done += step;
+    }
+}
@@ -0,0 +1,198 @@
+//! Monochrome (1 bit per pixel) Windows BMP.
+//!
+//! Rows are stored bottom-up and padded to four bytes, eight pixels per byte
+//! with the first pixel in the most significant bit. Pixel values index a
+//! two-color palette; the writer maps set pixels to white at index 1.
+
+use std::io::{Read, Write};
+
+use super::{read_growing, DecodingError};
+use crate::{bits, BinaryImage, Bit};
+
+const FILE_HEADER_LEN: u32 = 14;
+const INFO_HEADER_LEN: u32 = 40;
+const CORE_HEADER_LEN: u32 = 12;
+const PALETTE_LEN: u32 = 8;
+
+/// Number of bytes in a row padded to a 32-bit boundary.
+fn stride(width: u32) -> usize {
+    (width as usize).div_ceil(32) * 4
+}
+
+/// Decodes a 1-bpp uncompressed BMP.
+///
+/// Both `BITMAPCOREHEADER` and `BITMAPINFOHEADER` (including its V4 and V5
+/// extensions) are accepted. A palette color maps to a set pixel if any of its
+/// channels is nonzero.
+///
+/// # Errors
+///
+/// Returns an error if the data is not a BMP, is truncated, or is not an
+/// uncompressed 1-bpp bitmap.
+pub fn decode<R: Read>(mut reader: R) -> Result<BinaryImage, DecodingError> {
+    let mut file_header = [0; FILE_HEADER_LEN as usize];
+    reader.read_exact(&mut file_header)?;
+    if &file_header[..2] != b"BM" {
+        return Err(DecodingError::InvalidHeader("not a BMP file".into()));
+    }
+    let data_offset = u32_at(&file_header, 10);
+
+    let mut header_len = [0; 4];
+    reader.read_exact(&mut header_len)?;
+    let header_len = u32::from_le_bytes(header_len);
+    if header_len != CORE_HEADER_LEN && !(INFO_HEADER_LEN..=1024).contains(&header_len) {
+        return Err(DecodingError::Unsupported(format!(
+            "{header_len}-byte info header"
+        )));
+    }
+    let mut header = vec![0; header_len as usize - 4];
+    reader.read_exact(&mut header)?;
+
+    let (width, height, bit_count, colors, entry_len) = if header_len == CORE_HEADER_LEN {
+        let width = i32::from(u16_at(&header, 0));
+        let height = i32::from(u16_at(&header, 2));
+        (width, height, u16_at(&header, 6), 2, 3)
+    } else {
+        let compression = u32_at(&header, 12);
+        if compression != 0 {
+            return Err(DecodingError::Unsupported(format!(
+                "compression method {compression}"
+            )));
+        }
+        let colors = match u32_at(&header, 28) {
+            0 => 2,
+            colors => colors,
+        };
+        #[allow(clippy::cast_possible_wrap)]
+        (
+            u32_at(&header, 0) as i32,
+            u32_at(&header, 4) as i32,
+            u16_at(&header, 10),
+            colors,
+            4,
+        )
+    };
+    if bit_count != 1 {
+        return Err(DecodingError::Unsupported(format!(
+            "{bit_count} bits per pixel"
+        )));
+    }
+    let top_down = height < 0;
+    let (Ok(width), Some(height)) = (u32::try_from(width), height.checked_abs()) else {
+        return Err(DecodingError::InvalidHeader("invalid dimensions".into()));
+    };
+    #[allow(clippy::cast_sign_loss)]
+    let height = height as u32;
+    if width.checked_mul(height).is_none() {
+        return Err(DecodingError::InvalidHeader("image too large".into()));
+    }
+
+    let palette_len = colors
+        .checked_mul(entry_len)
+        .filter(|&len| len <= 1 << 16)
+        .ok_or_else(|| DecodingError::InvalidHeader("invalid palette size".into()))?;
+    let mut palette = vec![0; palette_len as usize];
+    reader.read_exact(&mut palette)?;
+    let color = |index: usize| {
+        palette
+            .chunks_exact(entry_len as usize)
+            .nth(index)
+            .map_or(Bit(false), |bgr| {
+                Bit::from(image::Rgb([bgr[2], bgr[1], bgr[0]]))
+            })
+    };
+    let (zero, one) = (color(0), color(1));
+
+    let consumed = FILE_HEADER_LEN + header_len + palette_len;
+    let gap = data_offset
+        .checked_sub(consumed)
+        .ok_or_else(|| DecodingError::InvalidHeader("pixel data overlaps header".into()))?;
+    std::io::copy(
+        &mut (&mut reader).take(u64::from(gap)),
+        &mut std::io::sink(),
+    )?;
+
+    // Read the rows before allocating the image, so a header claiming a huge
+    // image fails on the missing data instead.
+    let stride = stride(width);
+    let data = read_growing(&mut reader, stride as u64 * u64::from(height))?;
+    let mut image = BinaryImage::new(width, height);
+    for (i, row) in (0..height).zip(data.chunks(stride.max(1))) {
+        let y = if top_down { i } else { height - 1 - i };
+        image.unpack_row(y, row, true, *zero);
+    }
+    if zero == one {
+        let len = image.buffer.len();
+        bits::fill(&mut image.buffer, 0, len, *zero);
+    }
+    Ok(image)
+}
+
+/// Encodes `image` as an uncompressed 1-bpp BMP with a black and white
+/// palette.
+///
+/// # Errors
+///
+/// Returns an error if writing fails or the image is too large for the
+/// format.
+pub fn encode<W: Write>(image: &BinaryImage, mut writer: W) -> std::io::Result<()> {
+    let (width, height) = (image.width(), image.height());
+    let stride = stride(width);
+    let too_large =
+        || std::io::Error::new(std::io::ErrorKind::InvalidInput, "image too large for BMP");
+    let data_offset = FILE_HEADER_LEN + INFO_HEADER_LEN + PALETTE_LEN;
+    let data_len = u32::try_from(stride * height as usize).map_err(|_| too_large())?;
+    let file_len = data_len.checked_add(data_offset).ok_or_else(too_large)?;
+    let signed_width = i32::try_from(width).map_err(|_| too_large())?;
+    let signed_height = i32::try_from(height).map_err(|_| too_large())?;
+
+    let mut header = Vec::with_capacity(data_offset as usize);
+    header.extend_from_slice(b"BM");
+    header.extend_from_slice(&file_len.to_le_bytes());
+    header.extend_from_slice(&[0; 4]);
+    header.extend_from_slice(&data_offset.to_le_bytes());
+    header.extend_from_slice(&INFO_HEADER_LEN.to_le_bytes());
+    header.extend_from_slice(&signed_width.to_le_bytes());
+    header.extend_from_slice(&signed_height.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes()); // planes
+    header.extend_from_slice(&1u16.to_le_bytes()); // bits per pixel
+    header.extend_from_slice(&0u32.to_le_bytes()); // BI_RGB
+    header.extend_from_slice(&data_len.to_le_bytes());
+    header.extend_from_slice(&[0; 8]); // resolution
+    header.extend_from_slice(&2u32.to_le_bytes()); // colors used
+    header.extend_from_slice(&0u32.to_le_bytes()); // colors important
+    header.extend_from_slice(&[0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0]);
+    writer.write_all(&header)?;
+
+    let mut row = vec![0; stride];
+    for y in (0..height).rev() {
+        image.pack_row(y, &mut row, true, false);
+        writer.write_all(&row)?;
+    }
+    Ok(())
+}
+
+impl BinaryImage {
+    /// Encodes the image as a 1-bpp BMP, see [`encode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails or the image is too large for the
+    /// format.
+    pub fn write_bmp<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        encode(self, writer)
+    }
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
@@ -0,0 +1,671 @@
+//! CCITT Group 3 (T.4) and Group 4 (T.6) fax compression.
+//!
+//! Rows are coded as runs of white and black pixels, either on their own
+//! (one-dimensional, Modified Huffman) or relative to the previous row
+//! (two-dimensional, Modified READ). The options follow the parameters of the
+//! PDF `CCITTFaxDecode` filter, which also cover the TIFF compression schemes
+//! 2, 3 and 4.
+//!
+//! Black pixels are clear and white pixels set, unless
+//! [`black_is_1`](CcittOptions::black_is_1) is given.
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss
+)]
+
+use std::sync::OnceLock;
+
+use super::DecodingError;
+use crate::{bits, BinaryImage};
+
+/// Coding parameters of a CCITT bitstream.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CcittOptions {
+    /// Negative for pure two-dimensional Group 4 coding, zero for
+    /// one-dimensional Group 3 coding, and positive for mixed Group 3 coding
+    /// where at most `k - 1` two-dimensional rows follow each
+    /// one-dimensional row.
+    pub k: i32,
+    /// Rows are preceded by an end-of-line code.
+    pub end_of_line: bool,
+    /// Rows start on a byte boundary. With end-of-line codes, fill bits are
+    /// inserted so that each code ends on a byte boundary instead.
+    pub encoded_byte_align: bool,
+    /// The data is terminated by an end-of-block pattern: RTC for Group 3,
+    /// EOFB for Group 4.
+    pub end_of_block: bool,
+    /// Black pixels are set instead of white ones.
+    pub black_is_1: bool,
+}
+
+impl Default for CcittOptions {
+    /// The defaults of the PDF `CCITTFaxDecode` filter: one-dimensional
+    /// coding terminated by an end-of-block pattern.
+    fn default() -> Self {
+        Self {
+            k: 0,
+            end_of_line: false,
+            encoded_byte_align: false,
+            end_of_block: true,
+            black_is_1: false,
+        }
+    }
+}
+
+impl CcittOptions {
+    /// Group 4 coding without an end-of-block pattern, as in TIFF files.
+    #[must_use]
+    pub fn group4() -> Self {
+        Self {
+            k: -1,
+            end_of_block: false,
+            ..Self::default()
+        }
+    }
+
+    /// Group 3 coding with end-of-line codes, one-dimensional if `k` is 0.
+    #[must_use]
+    pub fn group3(k: i32) -> Self {
+        Self {
+            k: k.max(0),
+            end_of_line: true,
+            end_of_block: false,
+            ..Self::default()
+        }
+    }
+}
+
+/// A variable-length code: value and length in bits.
+type Code = (u16, u8);
+
+const EOL: Code = (0b0000_0000_0001, 12);
+
+#[rustfmt::skip]
+const WHITE_TERMINATING: [Code; 64] = [
+    (0b0011_0101, 8), (0b00_0111, 6), (0b0111, 4), (0b1000, 4),
+    (0b1011, 4), (0b1100, 4), (0b1110, 4), (0b1111, 4),
+    (0b1_0011, 5), (0b1_0100, 5), (0b0_0111, 5), (0b0_1000, 5),
+    (0b00_1000, 6), (0b00_0011, 6), (0b11_0100, 6), (0b11_0101, 6),
+    (0b10_1010, 6), (0b10_1011, 6), (0b010_0111, 7), (0b000_1100, 7),
+    (0b000_1000, 7), (0b001_0111, 7), (0b000_0011, 7), (0b000_0100, 7),
+    (0b010_1000, 7), (0b010_1011, 7), (0b001_0011, 7), (0b010_0100, 7),
+    (0b001_1000, 7), (0b0000_0010, 8), (0b0000_0011, 8), (0b0001_1010, 8),
+    (0b0001_1011, 8), (0b0001_0010, 8), (0b0001_0011, 8), (0b0001_0100, 8),
+    (0b0001_0101, 8), (0b0001_0110, 8), (0b0001_0111, 8), (0b0010_1000, 8),
+    (0b0010_1001, 8), (0b0010_1010, 8), (0b0010_1011, 8), (0b0010_1100, 8),
+    (0b0010_1101, 8), (0b0000_0100, 8), (0b0000_0101, 8), (0b0000_1010, 8),
+    (0b0000_1011, 8), (0b0101_0010, 8), (0b0101_0011, 8), (0b0101_0100, 8),
+    (0b0101_0101, 8), (0b0010_0100, 8), (0b0010_0101, 8), (0b0101_1000, 8),
+    (0b0101_1001, 8), (0b0101_1010, 8), (0b0101_1011, 8), (0b0100_1010, 8),
+    (0b0100_1011, 8), (0b0011_0010, 8), (0b0011_0011, 8), (0b0011_0100, 8),
+];
+
+/// Make-up codes for 64, 128, ..., 1728.
+#[rustfmt::skip]
+const WHITE_MAKEUP: [Code; 27] = [
+    (0b1_1011, 5), (0b1_0010, 5), (0b01_0111, 6), (0b011_0111, 7),
+    (0b0011_0110, 8), (0b0011_0111, 8), (0b0110_0100, 8), (0b0110_0101, 8),
+    (0b0110_1000, 8), (0b0110_0111, 8), (0b0_1100_1100, 9), (0b0_1100_1101, 9),
+    (0b0_1101_0010, 9), (0b0_1101_0011, 9), (0b0_1101_0100, 9), (0b0_1101_0101, 9),
+    (0b0_1101_0110, 9), (0b0_1101_0111, 9), (0b0_1101_1000, 9), (0b0_1101_1001, 9),
+    (0b0_1101_1010, 9), (0b0_1101_1011, 9), (0b0_1001_1000, 9), (0b0_1001_1001, 9),
+    (0b0_1001_1010, 9), (0b01_1000, 6), (0b0_1001_1011, 9),
+];
+
+#[rustfmt::skip]
+const BLACK_TERMINATING: [Code; 64] = [
+    (0b00_0011_0111, 10), (0b010, 3), (0b11, 2), (0b10, 2),
+    (0b011, 3), (0b0011, 4), (0b0010, 4), (0b0_0011, 5),
+    (0b00_0101, 6), (0b00_0100, 6), (0b000_0100, 7), (0b000_0101, 7),
+    (0b000_0111, 7), (0b0000_0100, 8), (0b0000_0111, 8), (0b0_0001_1000, 9),
+    (0b00_0001_0111, 10), (0b00_0001_1000, 10), (0b00_0000_1000, 10), (0b000_0110_0111, 11),
+    (0b000_0110_1000, 11), (0b000_0110_1100, 11), (0b000_0011_0111, 11), (0b000_0010_1000, 11),
+    (0b000_0001_0111, 11), (0b000_0001_1000, 11), (0b0000_1100_1010, 12), (0b0000_1100_1011, 12),
+    (0b0000_1100_1100, 12), (0b0000_1100_1101, 12), (0b0000_0110_1000, 12), (0b0000_0110_1001, 12),
+    (0b0000_0110_1010, 12), (0b0000_0110_1011, 12), (0b0000_1101_0010, 12), (0b0000_1101_0011, 12),
+    (0b0000_1101_0100, 12), (0b0000_1101_0101, 12), (0b0000_1101_0110, 12), (0b0000_1101_0111, 12),
+    (0b0000_0110_1100, 12), (0b0000_0110_1101, 12), (0b0000_1101_1010, 12), (0b0000_1101_1011, 12),
+    (0b0000_0101_0100, 12), (0b0000_0101_0101, 12), (0b0000_0101_0110, 12), (0b0000_0101_0111, 12),
+    (0b0000_0110_0100, 12), (0b0000_0110_0101, 12), (0b0000_0101_0010, 12), (0b0000_0101_0011, 12),
+    (0b0000_0010_0100, 12), (0b0000_0011_0111, 12), (0b0000_0011_1000, 12), (0b0000_0010_0111, 12),
+    (0b0000_0010_1000, 12), (0b0000_0101_1000, 12), (0b0000_0101_1001, 12), (0b0000_0010_1011, 12),
+    (0b0000_0010_1100, 12), (0b0000_0101_1010, 12), (0b0000_0110_0110, 12), (0b0000_0110_0111, 12),
+];
+
+/// Make-up codes for 64, 128, ..., 1728.
+#[rustfmt::skip]
+const BLACK_MAKEUP: [Code; 27] = [
+    (0b00_0000_1111, 10), (0b0000_1100_1000, 12), (0b0000_1100_1001, 12), (0b0000_0101_1011, 12),
+    (0b0000_0011_0011, 12), (0b0000_0011_0100, 12), (0b0000_0011_0101, 12), (0b0_0000_0110_1100, 13),
+    (0b0_0000_0110_1101, 13), (0b0_0000_0100_1010, 13), (0b0_0000_0100_1011, 13), (0b0_0000_0100_1100, 13),
+    (0b0_0000_0100_1101, 13), (0b0_0000_0111_0010, 13), (0b0_0000_0111_0011, 13), (0b0_0000_0111_0100, 13),
+    (0b0_0000_0111_0101, 13), (0b0_0000_0111_0110, 13), (0b0_0000_0111_0111, 13), (0b0_0000_0101_0010, 13),
+    (0b0_0000_0101_0011, 13), (0b0_0000_0101_0100, 13), (0b0_0000_0101_0101, 13), (0b0_0000_0101_1010, 13),
+    (0b0_0000_0101_1011, 13), (0b0_0000_0110_0100, 13), (0b0_0000_0110_0101, 13),
+];
+
+/// Make-up codes shared by both colors for 1792, 1856, ..., 2560.
+#[rustfmt::skip]
+const EXTENDED_MAKEUP: [Code; 13] = [
+    (0b000_0000_1000, 11), (0b000_0000_1100, 11), (0b000_0000_1101, 11), (0b0000_0001_0010, 12),
+    (0b0000_0001_0011, 12), (0b0000_0001_0100, 12), (0b0000_0001_0101, 12), (0b0000_0001_0110, 12),
+    (0b0000_0001_0111, 12), (0b0000_0001_1100, 12), (0b0000_0001_1101, 12), (0b0000_0001_1110, 12),
+    (0b0000_0001_1111, 12),
+];
+
+const PASS: Code = (0b0001, 4);
+const HORIZONTAL: Code = (0b001, 3);
+/// Vertical mode codes for a1 - b1 = -3..=3.
+#[rustfmt::skip]
+const VERTICAL: [Code; 7] = [
+    (0b000_0010, 7), (0b00_0010, 6), (0b010, 3), (0b1, 1),
+    (0b011, 3), (0b00_0011, 6), (0b000_0011, 7),
+];
+
+/// Longest run code in bits.
+const MAX_RUN_CODE: u8 = 13;
+/// Longest mode code in bits, excluding EOL.
+const MAX_MODE_CODE: u8 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Pass,
+    Horizontal,
+    Vertical(i8),
+    /// The uncompressed extension, which is not supported.
+    Extension,
+}
+
+/// Lookup table indexed by the next `MAX_RUN_CODE` bits, holding the run
+/// length and code length, or `None` for invalid codes.
+type RunTable = Vec<Option<(u16, u8)>>;
+
+fn run_tables() -> &'static [RunTable; 2] {
+    static TABLES: OnceLock<[RunTable; 2]> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let build = |terminating: &[Code; 64], makeup: &[Code; 27]| {
+            let mut table = vec![None; 1 << MAX_RUN_CODE];
+            let codes = terminating
+                .iter()
+                .zip(0..)
+                .chain(makeup.iter().zip((64..).step_by(64)))
+                .chain(EXTENDED_MAKEUP.iter().zip((1792..).step_by(64)));
+            for (&(code, len), run) in codes {
+                let shift = MAX_RUN_CODE - len;
+                let start = usize::from(code) << shift;
+                table[start..start + (1 << shift)].fill(Some((run, len)));
+            }
+            table
+        };
+        [
+            build(&WHITE_TERMINATING, &WHITE_MAKEUP),
+            build(&BLACK_TERMINATING, &BLACK_MAKEUP),
+        ]
+    })
+}
+
+fn mode_table() -> &'static [Option<(Mode, u8)>] {
+    static TABLE: OnceLock<Vec<Option<(Mode, u8)>>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = vec![None; 1 << MAX_MODE_CODE];
+        let codes = [
+            (PASS, Mode::Pass),
+            (HORIZONTAL, Mode::Horizontal),
+            ((0b000_0001, 7), Mode::Extension),
+        ]
+        .into_iter()
+        .chain(
+            VERTICAL
+                .iter()
+                .zip(-3..)
+                .map(|(&code, delta)| (code, Mode::Vertical(delta))),
+        );
+        for ((code, len), mode) in codes {
+            let shift = MAX_MODE_CODE - len;
+            let start = usize::from(code) << shift;
+            table[start..start + (1 << shift)].fill(Some((mode, len)));
+        }
+        table
+    })
+}
+
+/// Reads bits most significant first, yielding zeros past the end.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl BitReader<'_> {
+    fn remaining(&self) -> usize {
+        (self.data.len() * 8).saturating_sub(self.position)
+    }
+
+    /// Returns the next `len` bits, at most 24.
+    fn peek(&self, len: u8) -> u32 {
+        let start = self.position / 8;
+        let window = (0..4).fold(0, |window, i| {
+            (window << 8) | u32::from(self.data.get(start + i).copied().unwrap_or(0))
+        });
+        (window >> (32 - self.position % 8 - usize::from(len))) & bits::low_mask(len.into())
+    }
+
+    fn consume(&mut self, len: u8) -> Result<(), DecodingError> {
+        if self.remaining() < usize::from(len) {
+            return Err(DecodingError::UnexpectedEof);
+        }
+        self.position += usize::from(len);
+        Ok(())
+    }
+
+    fn align(&mut self) {
+        self.position = self.position.next_multiple_of(8);
+    }
+
+    /// Whether only zero bits are left.
+    fn exhausted(&self) -> bool {
+        let byte = self.position / 8;
+        self.data.get(byte).is_none_or(|&first| {
+            first & (0xFF >> (self.position % 8)) == 0
+                && self.data[byte + 1..].iter().all(|&b| b == 0)
+        })
+    }
+
+    /// Consumes an end-of-line code preceded by any number of fill bits.
+    fn skip_eol(&mut self) -> bool {
+        let mut position = self.position;
+        let mut zeros = 0;
+        while let Some(byte) = self.data.get(position / 8) {
+            if (byte >> (7 - position % 8)) & 1 == 1 {
+                if zeros >= 11 {
+                    self.position = position + 1;
+                    return true;
+                }
+                return false;
+            }
+            zeros += 1;
+            position += 1;
+        }
+        false
+    }
+
+    fn read_run(&mut self, black: bool) -> Result<u32, DecodingError> {
+        let table = &run_tables()[usize::from(black)];
+        let mut total = 0;
+        loop {
+            let Some((run, len)) = table[self.peek(MAX_RUN_CODE) as usize] else {
+                return Err(self.invalid("invalid run code"));
+            };
+            self.consume(len)?;
+            total += u32::from(run);
+            if run < 64 {
+                return Ok(total);
+            }
+        }
+    }
+
+    fn read_mode(&mut self) -> Result<Mode, DecodingError> {
+        match mode_table()[self.peek(MAX_MODE_CODE) as usize] {
+            Some((Mode::Extension, _)) => {
+                Err(DecodingError::Unsupported("uncompressed mode".into()))
+            }
+            Some((mode, len)) => {
+                self.consume(len)?;
+                Ok(mode)
+            }
+            None => Err(self.invalid("invalid mode code")),
+        }
+    }
+
+    /// An error for an unknown code, or for running out of data if only
+    /// padding is left.
+    fn invalid(&self, message: &str) -> DecodingError {
+        if self.exhausted() {
+            DecodingError::UnexpectedEof
+        } else {
+            DecodingError::InvalidData(message.into())
+        }
+    }
+}
+
+/// Writes bits most significant first.
+#[derive(Default)]
+struct BitWriter {
+    data: Vec<u8>,
+    position: usize,
+}
+
+impl BitWriter {
+    fn put(&mut self, (code, len): Code) {
+        for i in (0..len).rev() {
+            if self.position.is_multiple_of(8) {
+                self.data.push(0);
+            }
+            if (code >> i) & 1 == 1 {
+                *self.data.last_mut().unwrap() |= 0x80 >> (self.position % 8);
+            }
+            self.position += 1;
+        }
+    }
+
+    fn align(&mut self) {
+        self.position = self.position.next_multiple_of(8);
+    }
+
+    /// Writes an end-of-line code, padded so that it ends on a byte boundary
+    /// if `aligned`.
+    fn put_eol(&mut self, aligned: bool) {
+        if aligned {
+            while self.position % 8 != 4 {
+                self.put((0, 1));
+            }
+        }
+        self.put(EOL);
+    }
+
+    fn put_run(&mut self, mut run: u32, black: bool) {
+        let (terminating, makeup) = if black {
+            (&BLACK_TERMINATING, &BLACK_MAKEUP)
+        } else {
+            (&WHITE_TERMINATING, &WHITE_MAKEUP)
+        };
+        while run >= 2560 {
+            self.put(EXTENDED_MAKEUP[12]);
+            run -= 2560;
+        }
+        if run >= 1792 {
+            self.put(EXTENDED_MAKEUP[(run / 64 - 28) as usize]);
+        } else if run >= 64 {
+            self.put(makeup[(run / 64 - 1) as usize]);
+        }
+        self.put(terminating[(run % 64) as usize]);
+    }
+}
+
+/// Positions where the color changes, starting from white, followed by two
+/// `width` sentinels so that `b1` and `b2` always exist.
+fn changes(image: &BinaryImage, y: u32, black_is_1: bool, out: &mut Vec<u32>) {
+    out.clear();
+    let width = image.width() as usize;
+    let offset = image.row_offset(y);
+    let mut previous = 0; // imaginary white pixel before the row
+    let mut start = 0;
+    while start < width {
+        let len = (width - start).min(bits::WORD);
+        let mut word = bits::read(&image.buffer, offset + start, len);
+        if !black_is_1 {
+            word = !word & bits::low_mask(len);
+        }
+        let mut toggles = (word ^ ((word << 1) | previous)) & bits::low_mask(len);
+        while toggles != 0 {
+            out.push((start + toggles.trailing_zeros() as usize) as u32);
+            toggles &= toggles - 1;
+        }
+        previous = (word >> (len - 1)) & 1;
+        start += len;
+    }
+    out.extend([image.width(); 2]);
+}
+
+/// Index of `b1`: the first change after `a0` to the color opposite to the
+/// one at `a0`, searching `reference` from `from`. Stops at the sentinels.
+fn find_b1(reference: &[u32], a0: i64, black: bool, mut from: usize) -> usize {
+    let end = reference[reference.len() - 1];
+    while reference[from] < end && (i64::from(reference[from]) <= a0 || (from % 2 == 1) != black) {
+        from += 1;
+    }
+    from
+}
+
+/// Appends a change at `position`, dropping both if the previous change is at
+/// the same place.
+fn push_change(out: &mut Vec<u32>, position: u32) {
+    if out.last() == Some(&position) {
+        out.pop();
+    } else {
+        out.push(position);
+    }
+}
+
+fn decode_1d(reader: &mut BitReader, width: u32, out: &mut Vec<u32>) -> Result<(), DecodingError> {
+    let mut position = 0;
+    let mut black = false;
+    loop {
+        position += reader.read_run(black)?;
+        if position >= width {
+            if position > width {
+                return Err(DecodingError::InvalidData("run past end of row".into()));
+            }
+            return Ok(());
+        }
+        push_change(out, position);
+        black = !black;
+    }
+}
+
+fn decode_2d(
+    reader: &mut BitReader,
+    width: u32,
+    reference: &[u32],
+    out: &mut Vec<u32>,
+) -> Result<(), DecodingError> {
+    let width_i = i64::from(width);
+    let past_end = || DecodingError::InvalidData("run past end of row".into());
+    let mut a0: i64 = -1;
+    let mut black = false;
+    let mut b = 0usize;
+    while a0 < width_i {
+        b = find_b1(reference, a0, black, b.saturating_sub(2));
+        let b1 = i64::from(reference[b]);
+        let b2 = i64::from(reference[b + 1]);
+        match reader.read_mode()? {
+            Mode::Pass => a0 = b2,
+            Mode::Horizontal => {
+                let a1 = a0.max(0) + i64::from(reader.read_run(black)?);
+                let a2 = a1 + i64::from(reader.read_run(!black)?);
+                if a2 > width_i {
+                    return Err(past_end());
+                }
+                for change in [a1, a2] {
+                    if change < width_i {
+                        push_change(out, change as u32);
+                    }
+                }
+                a0 = a2;
+            }
+            Mode::Vertical(delta) => {
+                let a1 = b1 + i64::from(delta);
+                if a1 < a0.max(0) || a1 > width_i {
+                    return Err(past_end());
+                }
+                if a1 < width_i {
+                    push_change(out, a1 as u32);
+                }
+                a0 = a1;
+                black = !black;
+            }
+            Mode::Extension => unreachable!(),
+        }
+    }
+    Ok(())
+}
+
+/// Decodes a CCITT bitstream into an image `width` pixels wide.
+///
+/// Without a `height`, rows are decoded until the end-of-block pattern or the
+/// end of the data.
+///
+/// # Errors
+///
+/// Returns an error if the data holds invalid codes, runs that overflow a row,
+/// uncompressed-mode extensions, or ends before `height` rows are decoded.
+pub fn decode(
+    data: &[u8],
+    width: u32,
+    height: Option<u32>,
+    options: &CcittOptions,
+) -> Result<BinaryImage, DecodingError> {
+    let mut reader = BitReader { data, position: 0 };
+    let mut rows: Vec<Vec<u32>> = Vec::new();
+    let mut reference = vec![width; 2];
+
+    while height.is_none_or(|height| rows.len() < height as usize) {
+        if options.encoded_byte_align && (options.k < 0 || !options.end_of_line) {
+            reader.align();
+        }
+        if options.k < 0 {
+            if reader.peek(24) == 0x00_1001 {
+                break; // EOFB
+            }
+        } else if reader.skip_eol() {
+            // A second EOL completes the RTC pattern.
+            let position = reader.position;
+            if options.k > 0 {
+                reader.consume(1)?;
+            }
+            if reader.skip_eol() {
+                break;
+            }
+            reader.position = position;
+        }
+        if height.is_none() && reader.exhausted() {
+            break;
+        }
+        let two_dimensional = match options.k {
+            k if k < 0 => true,
+            0 => false,
+            _ => {
+                let tag = reader.peek(1);
+                reader.consume(1)?;
+                tag == 0
+            }
+        };
+
+        let mut row = Vec::new();
+        if two_dimensional {
+            decode_2d(&mut reader, width, &reference, &mut row)?;
+        } else {
+            decode_1d(&mut reader, width, &mut row)?;
+        }
+        reference.clone_from(&row);
+        reference.extend([width; 2]);
+        rows.push(row);
+    }
+    if height.is_some_and(|height| rows.len() < height as usize) {
+        return Err(DecodingError::UnexpectedEof);
+    }
+
+    let height = u32::try_from(rows.len())
+        .ok()
+        .filter(|&height| width.checked_mul(height).is_some())
+        .ok_or_else(|| DecodingError::InvalidData("image too large".into()))?;
+    let mut image = BinaryImage::new(width, height);
+    let white = !options.black_is_1;
+    if white {
+        image.buffer.negate();
+    }
+    for (y, row) in (0..height).zip(&rows) {
+        let offset = image.row_offset(y);
+        for run in row.chunks(2) {
+            let end = run.get(1).copied().unwrap_or(width);
+            bits::fill(
+                &mut image.buffer,
+                offset + run[0] as usize,
+                (end - run[0]) as usize,
+                !white,
+            );
+        }
+    }
+    Ok(image)
+}
+
+/// Encodes `image` as a CCITT bitstream, padded to a whole byte.
+#[must_use]
+pub fn encode(image: &BinaryImage, options: &CcittOptions) -> Vec<u8> {
+    let width = image.width();
+    let width_i = i64::from(width);
+    let mut writer = BitWriter::default();
+    let mut reference = vec![width; 2];
+    let mut row = Vec::new();
+
+    for y in 0..image.height() {
+        changes(image, y, options.black_is_1, &mut row);
+        if options.k >= 0 && options.end_of_line {
+            writer.put_eol(options.encoded_byte_align);
+        } else if options.encoded_byte_align {
+            writer.align();
+        }
+        let two_dimensional = match options.k {
+            k if k < 0 => true,
+            0 => false,
+            k => {
+                let two_dimensional = y % k as u32 != 0;
+                writer.put((u16::from(!two_dimensional), 1));
+                two_dimensional
+            }
+        };
+
+        if two_dimensional {
+            let (mut a0, mut black, mut a, mut b) = (-1, false, 0, 0usize);
+            while a0 < width_i {
+                b = find_b1(&reference, a0, black, b.saturating_sub(2));
+                let (b1, b2) = (i64::from(reference[b]), i64::from(reference[b + 1]));
+                while i64::from(row[a]) <= a0 {
+                    a += 1;
+                }
+                let (a1, a2) = (i64::from(row[a]), i64::from(row[a + 1]));
+                if b2 < a1 {
+                    writer.put(PASS);
+                    a0 = b2;
+                } else if (a1 - b1).abs() <= 3 {
+                    writer.put(VERTICAL[(a1 - b1 + 3) as usize]);
+                    a0 = a1;
+                    black = !black;
+                } else {
+                    writer.put(HORIZONTAL);
+                    writer.put_run((a1 - a0.max(0)) as u32, black);
+                    writer.put_run((a2 - a1) as u32, !black);
+                    a0 = a2;
+                }
+            }
+        } else {
+            let mut position = 0;
+            for (i, &change) in row.iter().enumerate() {
+                writer.put_run(change - position, i % 2 == 1);
+                position = change;
+                if change == width {
+                    break;
+                }
+            }
+        }
+        std::mem::swap(&mut reference, &mut row);
+    }
+
+    if options.end_of_block {
+        if options.encoded_byte_align && (options.k < 0 || !options.end_of_line) {
+            writer.align();
+        }
+        if options.k < 0 {
+            writer.put(EOL);
+            writer.put(EOL);
+        } else {
+            for _ in 0..6 {
+                writer.put_eol(false);
+                if options.k > 0 {
+                    writer.put((1, 1));
+                }
+            }
+        }
+    }
+    writer.align();
+    writer.data
+}
+
+impl BinaryImage {
+    /// Encodes the image as a CCITT bitstream, see [`encode`].
+    #[must_use]
+    pub fn to_ccitt(&self, options: &CcittOptions) -> Vec<u8> {
+        encode(self, options)
+    }
+}
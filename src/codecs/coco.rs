@@ -0,0 +1,309 @@
+//! The run-length encoding of masks used by the COCO dataset.
+//!
+//! COCO stores a mask as alternating runs of clear and set pixels in
+//! column-major order, starting with a possibly empty run of clear pixels.
+//! The runs are kept either as a plain list (`"counts": [...]`) or compressed
+//! into the ASCII string produced by `pycocotools.mask.encode`, which this
+//! module reads and writes byte for byte. Set pixels are the foreground.
+//!
+//! COCO gives the size of a mask as `[height, width]`; the functions here take
+//! width and height in the usual order.
+
+use image::math::Rect;
+
+use super::DecodingError;
+use crate::{bits, view, BinaryImage, Connectivity};
+
+/// A mask as COCO run lengths.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CocoRle {
+    width: u32,
+    height: u32,
+    counts: Vec<u32>,
+}
+
+impl CocoRle {
+    /// Wraps uncompressed run lengths.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runs do not cover exactly `width * height`
+    /// pixels.
+    pub fn from_counts(width: u32, height: u32, counts: Vec<u32>) -> Result<Self, DecodingError> {
+        let total: u64 = counts.iter().map(|&count| u64::from(count)).sum();
+        if total != u64::from(width) * u64::from(height) {
+            return Err(DecodingError::InvalidData(format!(
+                "runs cover {total} pixels instead of {width}x{height}"
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            counts,
+        })
+    }
+
+    /// Parses the compressed string form of the run lengths.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string holds characters outside `'0'..='o'`,
+    /// ends inside a number, or its runs do not cover exactly
+    /// `width * height` pixels.
+    pub fn from_compressed(width: u32, height: u32, counts: &str) -> Result<Self, DecodingError> {
+        let mut decoded: Vec<u32> = Vec::new();
+        let mut chars = counts.bytes();
+        while let Some(first) = chars.next() {
+            let mut value = 0i64;
+            let mut shift = 0;
+            let mut next = Some(first);
+            loop {
+                let c = next.ok_or(DecodingError::UnexpectedEof)?;
+                let chunk = match c.checked_sub(48) {
+                    Some(chunk) if chunk < 0x40 && shift < 35 => i64::from(chunk),
+                    _ => {
+                        return Err(DecodingError::InvalidData(format!(
+                            "invalid run length character {:?}",
+                            char::from(c)
+                        )))
+                    }
+                };
+                value |= (chunk & 0x1F) << shift;
+                shift += 5;
+                if chunk & 0x20 == 0 {
+                    // Sign extend from the last chunk.
+                    if chunk & 0x10 != 0 {
+                        value |= -1 << shift;
+                    }
+                    break;
+                }
+                next = chars.next();
+            }
+            // From the fourth run on, runs are stored relative to the run of
+            // the same kind before.
+            if decoded.len() > 2 {
+                value += i64::from(decoded[decoded.len() - 2]);
+            }
+            let count = u32::try_from(value)
+                .map_err(|_| DecodingError::InvalidData(format!("invalid run length {value}")))?;
+            decoded.push(count);
+        }
+        Self::from_counts(width, height, decoded)
+    }
+
+    /// The compressed string form of the run lengths, as
+    /// `pycocotools.mask.encode` writes it.
+    #[must_use]
+    pub fn to_compressed(&self) -> String {
+        let mut out = String::with_capacity(2 * self.counts.len());
+        for (i, &count) in self.counts.iter().enumerate() {
+            let mut value = i64::from(count);
+            if i > 2 {
+                value -= i64::from(self.counts[i - 2]);
+            }
+            loop {
+                let mut chunk = value & 0x1F;
+                value >>= 5;
+                let more = if chunk & 0x10 == 0 {
+                    value != 0
+                } else {
+                    value != -1
+                };
+                if more {
+                    chunk |= 0x20;
+                }
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                out.push(char::from(chunk as u8 + 48));
+                if !more {
+                    break;
+                }
+            }
+        }
+        out
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The run lengths, clear pixels first.
+    #[must_use]
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    /// Iterates over the runs of set pixels as half-open ranges of
+    /// column-major pixel indices.
+    fn runs(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        let mut position = 0u64;
+        self.counts
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, &count)| {
+                let start = position;
+                position += u64::from(count);
+                (i % 2 == 1 && count > 0).then_some((start, position))
+            })
+    }
+
+    /// Number of set pixels.
+    #[must_use]
+    pub fn area(&self) -> u64 {
+        self.counts
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .map(|&count| u64::from(count))
+            .sum()
+    }
+
+    /// The bounding box of the set pixels as COCO's `[x, y, width, height]`,
+    /// all zero if no pixel is set.
+    #[must_use]
+    pub fn to_bbox(&self) -> [u32; 4] {
+        let height = u64::from(self.height);
+        let mut bounds: Option<Rect> = None;
+        for (start, end) in self.runs() {
+            let (first_x, last_x) = (start / height, (end - 1) / height);
+            // A run wrapping into the next column spans all rows in between.
+            let (first_y, last_y) = if first_x == last_x {
+                (start % height, (end - 1) % height)
+            } else {
+                (0, height - 1)
+            };
+            #[allow(clippy::cast_possible_truncation)]
+            let run = Rect {
+                x: first_x as u32,
+                y: first_y as u32,
+                width: (last_x - first_x + 1) as u32,
+                height: (last_y - first_y + 1) as u32,
+            };
+            bounds = Some(match bounds {
+                None => run,
+                Some(rect) => {
+                    let (x, y) = (rect.x.min(run.x), rect.y.min(run.y));
+                    Rect {
+                        x,
+                        y,
+                        width: (rect.x + rect.width).max(run.x + run.width) - x,
+                        height: (rect.y + rect.height).max(run.y + run.height) - y,
+                    }
+                }
+            });
+        }
+        bounds.map_or([0; 4], |rect| [rect.x, rect.y, rect.width, rect.height])
+    }
+
+    /// Number of pixels set in both masks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ.
+    #[must_use]
+    pub fn intersection_area(&self, other: &CocoRle) -> u64 {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "Dimensions mismatch"
+        );
+        let mut area = 0;
+        let mut b = other.runs().peekable();
+        for (start, end) in self.runs() {
+            while let Some(&(other_start, other_end)) = b.peek() {
+                area += end.min(other_end).saturating_sub(start.max(other_start));
+                if other_end > end {
+                    break;
+                }
+                b.next();
+            }
+        }
+        area
+    }
+
+    /// Intersection over union of two masks, 0 if both are empty, as
+    /// `pycocotools.mask.iou` computes it for non-crowd regions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn iou(&self, other: &CocoRle) -> f64 {
+        let intersection = self.intersection_area(other);
+        let union = self.area() + other.area() - intersection;
+        if union == 0 {
+            0.0
+        } else {
+            intersection as f64 / union as f64
+        }
+    }
+}
+
+impl From<&BinaryImage> for CocoRle {
+    fn from(image: &BinaryImage) -> Self {
+        // Rows of the transposed image are the columns of the mask.
+        let columns = view::transpose(image);
+        let mut counts = Vec::new();
+        let mut position = 0;
+        let len = image.width as usize * image.height as usize;
+        bits::for_each_run(&columns.buffer, 0, len, |start, end| {
+            #[allow(clippy::cast_possible_truncation)]
+            counts.extend([(start - position) as u32, (end - start) as u32]);
+            position = end;
+        });
+        if position < len || counts.is_empty() {
+            #[allow(clippy::cast_possible_truncation)]
+            counts.push((len - position) as u32);
+        }
+        Self {
+            width: image.width,
+            height: image.height,
+            counts,
+        }
+    }
+}
+
+impl From<&CocoRle> for BinaryImage {
+    fn from(rle: &CocoRle) -> Self {
+        let mut columns = BinaryImage::new(rle.height, rle.width);
+        for (start, end) in rle.runs() {
+            #[allow(clippy::cast_possible_truncation)]
+            bits::fill(
+                &mut columns.buffer,
+                start as usize,
+                (end - start) as usize,
+                true,
+            );
+        }
+        view::transpose(&columns)
+    }
+}
+
+/// The outer boundaries of the set regions as COCO polygons, flat lists of
+/// `x, y` pixel corner coordinates.
+///
+/// COCO polygons cannot describe holes, so holes are filled when the polygons
+/// are rasterized again; use [`CocoRle`] for masks with holes.
+#[must_use]
+pub fn polygons(image: &BinaryImage) -> Vec<Vec<f64>> {
+    image
+        .outlines(Connectivity::Eight)
+        .into_iter()
+        .filter(|outline| !outline.is_hole())
+        .map(|outline| {
+            outline
+                .points
+                .iter()
+                .flat_map(|&(x, y)| [f64::from(x), f64::from(y)])
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,263 @@
+//! GeoJSON and WKT output of connected components as polygons.
+//!
+//! Each component becomes one polygon per outer boundary, with the holes
+//! inside it as interior rings. Boundaries run along the pixel edges, and
+//! pixel corners are mapped to world coordinates with a GDAL geotransform.
+//!
+//! Rings are valid simple features: closed, not touching themselves,
+//! exteriors counter-clockwise and holes clockwise in world coordinates as
+//! RFC 7946 requires. Where pixels touch only at a corner, the outline through
+//! that corner is split, so rings of one polygon, or polygons of one
+//! component, may share single points.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::{BinaryImage, Connectivity, Outline};
+
+/// Options for [`geojson`], [`wkt`] and [`polygons`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoOptions {
+    /// Maps the pixel corner `(x, y)` to `(t[0] + x t[1] + y t[2], t[3] + x
+    /// t[4] + y t[5])`, as in GDAL. The default keeps pixel coordinates.
+    pub transform: [f64; 6],
+    /// Whether pixels touching only at a corner belong to the same component.
+    pub connectivity: Connectivity,
+}
+
+impl Default for GeoOptions {
+    fn default() -> Self {
+        Self {
+            transform: [0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+            connectivity: Connectivity::Eight,
+        }
+    }
+}
+
+/// A closed ring of world coordinates, the first point repeated at the end.
+pub type Ring = Vec<(f64, f64)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    pub exterior: Ring,
+    pub holes: Vec<Ring>,
+}
+
+/// The polygons of each component, indexed by label minus one as in
+/// [`BinaryImage::components`].
+#[must_use]
+pub fn polygons(image: &BinaryImage, options: &GeoOptions) -> Vec<Vec<Polygon>> {
+    let components = image.components(options.connectivity);
+    let mut exteriors = vec![Vec::new(); components.count() as usize];
+    let mut holes = Vec::new();
+    for outline in image.outlines(options.connectivity) {
+        for ring in simple_rings(&outline.points) {
+            let (x, y) = ring.inner_pixel();
+            let label = components.label(x, y) as usize - 1;
+            if ring.is_hole() {
+                holes.push((label, ring));
+            } else {
+                exteriors[label].push((ring, Vec::new()));
+            }
+        }
+    }
+    for (label, hole) in holes {
+        let polygons = &mut exteriors[label];
+        let index = if polygons.len() == 1 {
+            0
+        } else {
+            let point = hole_point(&hole);
+            polygons
+                .iter()
+                .position(|(exterior, _)| contains(exterior, point))
+                .unwrap_or_default()
+        };
+        polygons[index].1.push(hole);
+    }
+
+    let ring = |outline: &Outline, counter_clockwise: bool| {
+        world_ring(&outline.points, &options.transform, counter_clockwise)
+    };
+    exteriors
+        .iter()
+        .map(|polygons| {
+            polygons
+                .iter()
+                .map(|(exterior, holes)| Polygon {
+                    exterior: ring(exterior, true),
+                    holes: holes.iter().map(|hole| ring(hole, false)).collect(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Generates a GeoJSON `FeatureCollection` with one feature per component,
+/// a `Polygon` or a `MultiPolygon`, with the component label as property
+/// `label`.
+#[must_use]
+pub fn geojson(image: &BinaryImage, options: &GeoOptions) -> String {
+    let mut json = String::from(r#"{"type":"FeatureCollection","features":["#);
+    for (label, polygons) in (1..).zip(polygons(image, options)) {
+        if label > 1 {
+            json.push(',');
+        }
+        // Writing to a `String` cannot fail.
+        let _ = write!(
+            json,
+            r#"{{"type":"Feature","properties":{{"label":{label}}},"geometry":{{"type":"{}","coordinates":"#,
+            if polygons.len() == 1 {
+                "Polygon"
+            } else {
+                "MultiPolygon"
+            }
+        );
+        let polygon_json = |json: &mut String, polygon: &Polygon| {
+            json.push('[');
+            for (i, ring) in std::iter::once(&polygon.exterior)
+                .chain(&polygon.holes)
+                .enumerate()
+            {
+                if i > 0 {
+                    json.push(',');
+                }
+                json.push('[');
+                for (j, (x, y)) in ring.iter().enumerate() {
+                    let separator = if j > 0 { "," } else { "" };
+                    let _ = write!(json, "{separator}[{x},{y}]");
+                }
+                json.push(']');
+            }
+            json.push(']');
+        };
+        if let [polygon] = polygons.as_slice() {
+            polygon_json(&mut json, polygon);
+        } else {
+            json.push('[');
+            for (i, polygon) in polygons.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                polygon_json(&mut json, polygon);
+            }
+            json.push(']');
+        }
+        json.push_str("}}");
+    }
+    json.push_str("]}");
+    json
+}
+
+/// Generates a WKT `POLYGON` or `MULTIPOLYGON` for each component, indexed by
+/// label minus one.
+#[must_use]
+pub fn wkt(image: &BinaryImage, options: &GeoOptions) -> Vec<String> {
+    let polygon_wkt = |polygon: &Polygon| {
+        let rings: Vec<String> = std::iter::once(&polygon.exterior)
+            .chain(&polygon.holes)
+            .map(|ring| {
+                let points: Vec<String> = ring.iter().map(|(x, y)| format!("{x} {y}")).collect();
+                format!("({})", points.join(", "))
+            })
+            .collect();
+        format!("({})", rings.join(", "))
+    };
+    polygons(image, options)
+        .iter()
+        .map(|polygons| {
+            if let [polygon] = polygons.as_slice() {
+                format!("POLYGON {}", polygon_wkt(polygon))
+            } else {
+                let polygons: Vec<String> = polygons.iter().map(polygon_wkt).collect();
+                format!("MULTIPOLYGON ({})", polygons.join(", "))
+            }
+        })
+        .collect()
+}
+
+impl BinaryImage {
+    /// Generates a GeoJSON `FeatureCollection` of the components, see
+    /// [`geojson`].
+    #[must_use]
+    pub fn to_geojson(&self, options: &GeoOptions) -> String {
+        geojson(self, options)
+    }
+
+    /// Generates a WKT geometry for each component, see [`wkt`].
+    #[must_use]
+    pub fn to_wkt(&self, options: &GeoOptions) -> Vec<String> {
+        wkt(self, options)
+    }
+}
+
+/// Splits an outline at the vertices it passes twice into rings that do not
+/// touch themselves.
+fn simple_rings(points: &[(u32, u32)]) -> Vec<Outline> {
+    let mut rings = Vec::new();
+    let mut stack: Vec<(u32, u32)> = Vec::with_capacity(points.len());
+    let mut seen = HashMap::new();
+    for &point in points {
+        if let Some(&start) = seen.get(&point) {
+            for other in &stack[start + 1..] {
+                seen.remove(other);
+            }
+            // The ring starts at the repeated vertex.
+            let mut ring = vec![point];
+            ring.extend(stack.drain(start + 1..));
+            rings.push(Outline { points: ring });
+        } else {
+            seen.insert(point, stack.len());
+            stack.push(point);
+        }
+    }
+    // The ring through the first vertex goes first.
+    rings.insert(0, Outline { points: stack });
+    rings
+}
+
+/// The center of the clear pixel left of the first edge of a hole, in half
+/// pixels.
+fn hole_point(hole: &Outline) -> (i64, i64) {
+    let ((x0, y0), (x1, y1)) = (hole.points[0], hole.points[1]);
+    let (x0, y0) = (i64::from(x0), i64::from(y0));
+    let dx = (i64::from(x1) - x0).signum();
+    let dy = (i64::from(y1) - y0).signum();
+    (2 * x0 + dx + dy, 2 * y0 + dy - dx)
+}
+
+/// Whether `ring` encloses `point`, given in half pixels at a pixel center
+/// so it never lies on the ring.
+fn contains(ring: &Outline, (x, y): (i64, i64)) -> bool {
+    let points = &ring.points;
+    let mut inside = false;
+    for (&(x0, y0), &(x1, y1)) in points.iter().zip(points.iter().cycle().skip(1)) {
+        let (x0, y0) = (2 * i64::from(x0), 2 * i64::from(y0));
+        let (x1, y1) = (2 * i64::from(x1), 2 * i64::from(y1));
+        // Only vertical edges cross the horizontal ray to the right.
+        if x0 == x1 && x0 > x && (y0 < y) != (y1 < y) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Maps pixel corners to a closed ring of world coordinates with the given
+/// orientation.
+fn world_ring(points: &[(u32, u32)], t: &[f64; 6], counter_clockwise: bool) -> Ring {
+    let mut ring: Ring = points
+        .iter()
+        .chain(&points[..1])
+        .map(|&(x, y)| {
+            let (x, y) = (f64::from(x), f64::from(y));
+            (t[0] + x * t[1] + y * t[2], t[3] + x * t[4] + y * t[5])
+        })
+        .collect();
+    let twice_area: f64 = ring
+        .windows(2)
+        .map(|pair| pair[0].0 * pair[1].1 - pair[1].0 * pair[0].1)
+        .sum();
+    if (twice_area > 0.0) != counter_clockwise {
+        ring.reverse();
+    }
+    ring
+}
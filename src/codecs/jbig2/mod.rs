@@ -0,0 +1,275 @@
+//! The JBIG2 generic region coding procedure (T.88 section 6.2).
+//!
+//! This is the coding of a plain bitmap used by immediate generic region
+//! segments and by the `JBIG2Decode` filter of PDF files that embed them. The
+//! region data is either MMR coded, which is CCITT Group 4 handled by
+//! [`ccitt`](super::ccitt), or coded with the adaptive MQ arithmetic coder
+//! using one of four context templates. Segment headers and the other region
+//! types are not handled.
+//!
+//! JBIG2 stores black as 1, so set pixels are coded as 0.
+
+mod mq;
+
+pub(crate) use mq::{Context, MqDecoder, MqEncoder};
+
+use super::ccitt::{self, CcittOptions};
+use super::DecodingError;
+use crate::BinaryImage;
+
+/// Parameters of a generic region, as found in its segment header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GenericRegionParams {
+    /// MMR instead of arithmetic coding. The other fields are ignored then.
+    pub mmr: bool,
+    /// The context template `GBTEMPLATE`, 0 to 3. Template 0 uses 16 pixels,
+    /// templates 1, 2 and 3 use 13, 10 and 10.
+    pub template: u8,
+    /// Typical prediction (`TPGDON`): rows equal to the row above are coded
+    /// with a single decision.
+    pub typical_prediction: bool,
+    /// Offsets of the adaptive template pixels, relative to the coded pixel.
+    /// Template 0 uses all four, the others only the first. They must point
+    /// at pixels coded before, that is above or left on the same row.
+    pub adaptive_pixels: [(i8, i8); 4],
+}
+
+impl GenericRegionParams {
+    /// Arithmetic coding with `template` and its nominal adaptive pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `template` is greater than 3.
+    #[must_use]
+    pub fn arithmetic(template: u8) -> Self {
+        let first = match template {
+            0 | 1 => (3, -1),
+            2 | 3 => (2, -1),
+            _ => panic!("Invalid template {template}"),
+        };
+        Self {
+            mmr: false,
+            template,
+            typical_prediction: false,
+            adaptive_pixels: [first, (-3, -1), (2, -2), (-2, -2)],
+        }
+    }
+
+    /// MMR coding.
+    #[must_use]
+    pub fn mmr() -> Self {
+        Self {
+            mmr: true,
+            ..Self::arithmetic(0)
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.mmr {
+            return Ok(());
+        }
+        if self.template > 3 {
+            return Err(format!("invalid template {}", self.template));
+        }
+        let used = if self.template == 0 { 4 } else { 1 };
+        match self.adaptive_pixels[..used]
+            .iter()
+            .find(|&&(x, y)| y > 0 || (y == 0 && x >= 0))
+        {
+            Some((x, y)) => Err(format!("adaptive pixel ({x}, {y}) is not yet coded")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Context of the pseudo-pixel that flags typical rows, per template.
+const TYPICAL_CONTEXTS: [usize; 4] = [0x9B25, 0x0795, 0x00E5, 0x0195];
+
+/// The fixed template pixels per template, from the least significant context
+/// bit up. `None` marks the position of an adaptive pixel.
+#[rustfmt::skip]
+const TEMPLATES: [&[Option<(i8, i8)>]; 4] = [
+    &[
+        Some((-1, 0)), Some((-2, 0)), Some((-3, 0)), Some((-4, 0)), None,
+        Some((2, -1)), Some((1, -1)), Some((0, -1)), Some((-1, -1)), Some((-2, -1)), None, None,
+        Some((1, -2)), Some((0, -2)), Some((-1, -2)), None,
+    ],
+    &[
+        Some((-1, 0)), Some((-2, 0)), Some((-3, 0)), None,
+        Some((2, -1)), Some((1, -1)), Some((0, -1)), Some((-1, -1)), Some((-2, -1)),
+        Some((2, -2)), Some((1, -2)), Some((0, -2)), Some((-1, -2)),
+    ],
+    &[
+        Some((-1, 0)), Some((-2, 0)), None,
+        Some((1, -1)), Some((0, -1)), Some((-1, -1)), Some((-2, -1)),
+        Some((1, -2)), Some((0, -2)), Some((-1, -2)),
+    ],
+    &[
+        Some((-1, 0)), Some((-2, 0)), Some((-3, 0)), Some((-4, 0)), None,
+        Some((1, -1)), Some((0, -1)), Some((-1, -1)), Some((-2, -1)), Some((-3, -1)),
+    ],
+];
+
+/// The pixels being coded, one byte each with 1 for black.
+struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Bitmap {
+    fn get(&self, x: isize, y: isize) -> usize {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) if x < self.width && y < self.height => {
+                usize::from(self.pixels[y * self.width + x])
+            }
+            _ => 0,
+        }
+    }
+
+    /// The template pixels around `(x, y)`, in context bit order.
+    fn offsets(params: &GenericRegionParams) -> Vec<(isize, isize)> {
+        let mut adaptive = params.adaptive_pixels.iter();
+        TEMPLATES[usize::from(params.template)]
+            .iter()
+            .map(|&pixel| {
+                let (x, y) = pixel
+                    .or_else(|| adaptive.next().copied())
+                    .unwrap_or_default();
+                (isize::from(x), isize::from(y))
+            })
+            .collect()
+    }
+
+    fn context(&self, x: usize, y: usize, offsets: &[(isize, isize)]) -> usize {
+        let (x, y) = (x.cast_signed(), y.cast_signed());
+        offsets
+            .iter()
+            .enumerate()
+            .fold(0, |context, (bit, &(dx, dy))| {
+                context | self.get(x + dx, y + dy) << bit
+            })
+    }
+
+    /// Whether row `y` equals the row above, which is white for the first.
+    fn is_typical(&self, y: usize) -> bool {
+        let row = &self.pixels[y * self.width..(y + 1) * self.width];
+        if y == 0 {
+            row.iter().all(|&pixel| pixel == 0)
+        } else {
+            row == &self.pixels[(y - 1) * self.width..y * self.width]
+        }
+    }
+}
+
+/// Decodes a generic region of `width` by `height` pixels from `data`.
+///
+/// # Errors
+///
+/// Returns an error if the parameters are invalid or the MMR data is
+/// malformed. Truncated arithmetic-coded data decodes to garbage rather than
+/// an error, as the coder cannot detect it.
+pub fn decode_generic_region(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    params: &GenericRegionParams,
+) -> Result<BinaryImage, DecodingError> {
+    params.validate().map_err(DecodingError::InvalidHeader)?;
+    if width.checked_mul(height).is_none() {
+        return Err(DecodingError::InvalidHeader("image too large".into()));
+    }
+    if params.mmr {
+        // JBIG2 MMR is CCITT Group 4 with black as 1, optionally ended by
+        // EOFB.
+        return ccitt::decode(data, width, Some(height), &CcittOptions::group4());
+    }
+
+    let mut bitmap = Bitmap {
+        width: width as usize,
+        height: height as usize,
+        pixels: vec![0; width as usize * height as usize],
+    };
+    let offsets = Bitmap::offsets(params);
+    let mut contexts = vec![Context::default(); 1 << offsets.len()];
+    let mut decoder = MqDecoder::new(data);
+    let mut typical = false;
+    for y in 0..bitmap.height {
+        if params.typical_prediction {
+            typical ^=
+                decoder.decode(&mut contexts[TYPICAL_CONTEXTS[usize::from(params.template)]]) == 1;
+            if typical {
+                if y > 0 {
+                    let (above, row) = bitmap.pixels.split_at_mut(y * bitmap.width);
+                    row[..bitmap.width].copy_from_slice(&above[(y - 1) * bitmap.width..]);
+                }
+                continue;
+            }
+        }
+        for x in 0..bitmap.width {
+            let context = bitmap.context(x, y, &offsets);
+            bitmap.pixels[y * bitmap.width + x] = decoder.decode(&mut contexts[context]);
+        }
+    }
+
+    let mut image = BinaryImage::new(width, height);
+    for (i, &pixel) in bitmap.pixels.iter().enumerate() {
+        image.buffer.set(i, pixel == 0);
+    }
+    Ok(image)
+}
+
+/// Encodes `image` as generic region data.
+///
+/// MMR data is ended with EOFB, arithmetic-coded data with the `0xFF 0xAC`
+/// marker.
+///
+/// # Panics
+///
+/// Panics if the template is greater than 3 or an adaptive pixel points at a
+/// pixel that is not coded yet.
+#[must_use]
+pub fn encode_generic_region(image: &BinaryImage, params: &GenericRegionParams) -> Vec<u8> {
+    if let Err(message) = params.validate() {
+        panic!("Invalid generic region parameters: {message}");
+    }
+    if params.mmr {
+        return ccitt::encode(
+            image,
+            &CcittOptions {
+                end_of_block: true,
+                ..CcittOptions::group4()
+            },
+        );
+    }
+
+    let bitmap = Bitmap {
+        width: image.width() as usize,
+        height: image.height() as usize,
+        pixels: (0..image.width() as usize * image.height() as usize)
+            .map(|i| u8::from(!image.buffer[i]))
+            .collect(),
+    };
+    let offsets = Bitmap::offsets(params);
+    let mut contexts = vec![Context::default(); 1 << offsets.len()];
+    let mut encoder = MqEncoder::new();
+    let mut typical = false;
+    for y in 0..bitmap.height {
+        if params.typical_prediction {
+            let row_typical = bitmap.is_typical(y);
+            encoder.encode(
+                &mut contexts[TYPICAL_CONTEXTS[usize::from(params.template)]],
+                u8::from(row_typical != typical),
+            );
+            typical = row_typical;
+            if typical {
+                continue;
+            }
+        }
+        for x in 0..bitmap.width {
+            let context = bitmap.context(x, y, &offsets);
+            encoder.encode(&mut contexts[context], bitmap.pixels[y * bitmap.width + x]);
+        }
+    }
+    encoder.finish()
+}
@@ -0,0 +1,255 @@
+//! The MQ binary arithmetic coder of T.88 Annex E.
+#![allow(clippy::cast_possible_truncation, clippy::similar_names)]
+
+/// Probability estimation state: `Qe`, next index after an MPS and after an
+/// LPS, and whether an LPS switches the MPS sense.
+#[rustfmt::skip]
+const STATES: [(u16, u8, u8, bool); 47] = [
+    (0x5601, 1, 1, true), (0x3401, 2, 6, false), (0x1801, 3, 9, false),
+    (0x0AC1, 4, 12, false), (0x0521, 5, 29, false), (0x0221, 38, 33, false),
+    (0x5601, 7, 6, true), (0x5401, 8, 14, false), (0x4801, 9, 14, false),
+    (0x3801, 10, 14, false), (0x3001, 11, 17, false), (0x2401, 12, 18, false),
+    (0x1C01, 13, 20, false), (0x1601, 29, 21, false), (0x5601, 15, 14, true),
+    (0x5401, 16, 14, false), (0x5101, 17, 15, false), (0x4801, 18, 16, false),
+    (0x3801, 19, 17, false), (0x3401, 20, 18, false), (0x3001, 21, 19, false),
+    (0x2801, 22, 19, false), (0x2401, 23, 20, false), (0x2201, 24, 21, false),
+    (0x1C01, 25, 22, false), (0x1801, 26, 23, false), (0x1601, 27, 24, false),
+    (0x1401, 28, 25, false), (0x1201, 29, 26, false), (0x1101, 30, 27, false),
+    (0x0AC1, 31, 28, false), (0x09C1, 32, 29, false), (0x08A1, 33, 30, false),
+    (0x0521, 34, 31, false), (0x0441, 35, 32, false), (0x02A1, 36, 33, false),
+    (0x0221, 37, 34, false), (0x0141, 38, 35, false), (0x0111, 39, 36, false),
+    (0x0085, 40, 37, false), (0x0049, 41, 38, false), (0x0025, 42, 39, false),
+    (0x0015, 43, 40, false), (0x0009, 44, 41, false), (0x0005, 45, 42, false),
+    (0x0001, 45, 43, false), (0x5601, 46, 46, false),
+];
+
+/// Adaptive state of one context: index into `STATES` and the MPS.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Context {
+    index: u8,
+    mps: u8,
+}
+
+impl Context {
+    fn state(self) -> (u32, u8, u8, bool) {
+        let (qe, next_mps, next_lps, switch) = STATES[usize::from(self.index)];
+        (u32::from(qe), next_mps, next_lps, switch)
+    }
+}
+
+/// Decoder following the software conventions of T.88 E.3.
+pub(crate) struct MqDecoder<'a> {
+    data: &'a [u8],
+    position: usize,
+    a: u32,
+    c_high: u32,
+    c_low: u32,
+    ct: u32,
+}
+
+impl<'a> MqDecoder<'a> {
+    /// INITDEC.
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        let mut decoder = Self {
+            data,
+            position: 0,
+            a: 0,
+            c_high: u32::from(data.first().copied().unwrap_or(0xFF)),
+            c_low: 0,
+            ct: 0,
+        };
+        decoder.byte_in();
+        decoder.c_high = ((decoder.c_high << 7) & 0xFFFF) | ((decoder.c_low >> 9) & 0x7F);
+        decoder.c_low = (decoder.c_low << 7) & 0xFFFF;
+        decoder.ct -= 7;
+        decoder.a = 0x8000;
+        decoder
+    }
+
+    fn byte(&self, position: usize) -> u32 {
+        u32::from(self.data.get(position).copied().unwrap_or(0xFF))
+    }
+
+    /// BYTEIN. Past the end of the data, 1-bits are fed in as after a
+    /// marker.
+    fn byte_in(&mut self) {
+        if self.byte(self.position) == 0xFF {
+            if self.byte(self.position + 1) > 0x8F {
+                self.c_low += 0xFF00;
+                self.ct = 8;
+            } else {
+                self.position += 1;
+                self.c_low += self.byte(self.position) << 9;
+                self.ct = 7;
+            }
+        } else {
+            self.position += 1;
+            self.c_low += self.byte(self.position) << 8;
+            self.ct = 8;
+        }
+        if self.c_low > 0xFFFF {
+            self.c_high += self.c_low >> 16;
+            self.c_low &= 0xFFFF;
+        }
+    }
+
+    /// DECODE: reads one decision in `context`.
+    pub(crate) fn decode(&mut self, context: &mut Context) -> u8 {
+        let (qe, next_mps, next_lps, switch) = context.state();
+        let mut a = self.a - qe;
+        let mps = context.mps;
+        let lps = |context: &mut Context| {
+            if switch {
+                context.mps ^= 1;
+            }
+            context.index = next_lps;
+            mps ^ 1
+        };
+        let decision = if self.c_high < qe {
+            // LPS_EXCHANGE
+            let decision = if a < qe {
+                context.index = next_mps;
+                mps
+            } else {
+                lps(context)
+            };
+            a = qe;
+            decision
+        } else {
+            self.c_high -= qe;
+            if a & 0x8000 != 0 {
+                self.a = a;
+                return mps;
+            }
+            // MPS_EXCHANGE
+            if a < qe {
+                lps(context)
+            } else {
+                context.index = next_mps;
+                mps
+            }
+        };
+        // RENORMD
+        while a & 0x8000 == 0 {
+            if self.ct == 0 {
+                self.byte_in();
+            }
+            a <<= 1;
+            self.c_high = ((self.c_high << 1) & 0xFFFF) | ((self.c_low >> 15) & 1);
+            self.c_low = (self.c_low << 1) & 0xFFFF;
+            self.ct -= 1;
+        }
+        self.a = a;
+        decision
+    }
+}
+
+/// Encoder following T.88 E.2.
+pub(crate) struct MqEncoder {
+    /// Output, starting with the placeholder byte before the first one.
+    out: Vec<u8>,
+    a: u32,
+    c: u32,
+    ct: u32,
+}
+
+impl MqEncoder {
+    /// INITENC.
+    pub(crate) fn new() -> Self {
+        Self {
+            out: vec![0],
+            a: 0x8000,
+            c: 0,
+            ct: 12,
+        }
+    }
+
+    /// ENCODE: writes `decision` (0 or 1) in `context`.
+    pub(crate) fn encode(&mut self, context: &mut Context, decision: u8) {
+        let (qe, next_mps, next_lps, switch) = context.state();
+        self.a -= qe;
+        if decision == context.mps {
+            // CODEMPS
+            if self.a & 0x8000 != 0 {
+                self.c += qe;
+                return;
+            }
+            if self.a < qe {
+                self.a = qe;
+            } else {
+                self.c += qe;
+            }
+            context.index = next_mps;
+        } else {
+            // CODELPS
+            if self.a < qe {
+                self.c += qe;
+            } else {
+                self.a = qe;
+            }
+            if switch {
+                context.mps ^= 1;
+            }
+            context.index = next_lps;
+        }
+        // RENORME
+        while self.a & 0x8000 == 0 {
+            self.a <<= 1;
+            self.c <<= 1;
+            self.ct -= 1;
+            if self.ct == 0 {
+                self.byte_out();
+            }
+        }
+    }
+
+    /// BYTEOUT, with bit stuffing after 0xFF.
+    fn byte_out(&mut self) {
+        let last = self.out.last_mut().unwrap_or_else(|| unreachable!());
+        if *last == 0xFF {
+            self.push_stuffed();
+        } else if self.c < 0x0800_0000 {
+            self.push();
+        } else {
+            *last += 1;
+            if *last == 0xFF {
+                self.c &= 0x07FF_FFFF;
+                self.push_stuffed();
+            } else {
+                self.push();
+            }
+        }
+    }
+
+    fn push(&mut self) {
+        self.out.push((self.c >> 19) as u8);
+        self.c &= 0x7FFFF;
+        self.ct = 8;
+    }
+
+    fn push_stuffed(&mut self) {
+        self.out.push((self.c >> 20) as u8);
+        self.c &= 0xFFFFF;
+        self.ct = 7;
+    }
+
+    /// FLUSH: terminates the code with the `0xFF 0xAC` marker and returns it.
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        // SETBITS
+        let temp = self.c + self.a;
+        self.c |= 0xFFFF;
+        if self.c >= temp {
+            self.c -= 0x8000;
+        }
+        self.c <<= self.ct;
+        self.byte_out();
+        self.c <<= self.ct;
+        self.byte_out();
+        if self.out.last() != Some(&0xFF) {
+            self.out.push(0xFF);
+        }
+        self.out.push(0xAC);
+        self.out.remove(0);
+        self.out
+    }
+}
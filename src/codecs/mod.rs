@@ -0,0 +1,112 @@
+//! Native readers and writers for 1-bit image formats.
+//!
+//! Codecs move whole rows between the packed storage of a [`BinaryImage`] and
+//! the encoded bytes without expanding pixels to 8 bits.
+//!
+//! Set pixels are white, matching the conversions from [`image`] pixels where
+//! any nonzero value is set. Formats that store black as 1, such as PBM, are
+//! inverted on the fly, so decoding a file natively or through
+//! [`image::DynamicImage`] gives the same [`BinaryImage`].
+
+use std::io::Read;
+
+use derive_more::derive::{Display, Error};
+
+use crate::{bits, BinaryImage};
+
+pub mod bmp;
+pub mod ccitt;
+pub mod coco;
+pub mod geo;
+pub mod jbig2;
+pub mod pbm;
+#[cfg(feature = "png")]
+pub mod png;
+pub mod svg;
+pub mod tiff;
+pub mod xbm;
+
+/// An error raised while decoding an image.
+#[non_exhaustive]
+#[derive(Error, Display, Debug)]
+pub enum DecodingError {
+    /// Reading from the underlying source failed.
+    #[display("{_0}")]
+    Io(std::io::Error),
+
+    /// The data ended before the image was complete.
+    #[display("Unexpected end of data.")]
+    UnexpectedEof,
+
+    /// The header or metadata is malformed.
+    #[display("Invalid header: {_0}.")]
+    #[error(ignore)]
+    InvalidHeader(String),
+
+    /// The image data is malformed.
+    #[display("Invalid data: {_0}.")]
+    #[error(ignore)]
+    InvalidData(String),
+
+    /// The file is valid but uses a feature this crate does not handle.
+    #[display("Unsupported: {_0}.")]
+    #[error(ignore)]
+    Unsupported(String),
+}
+
+/// Reads exactly `len` bytes into a buffer that grows as data arrives, so a
+/// header claiming a huge image fails with [`DecodingError::UnexpectedEof`]
+/// instead of allocating for it up front.
+pub(crate) fn read_growing<R: Read>(reader: R, len: u64) -> Result<Vec<u8>, DecodingError> {
+    let mut data = Vec::new();
+    reader.take(len).read_to_end(&mut data)?;
+    if (data.len() as u64) < len {
+        return Err(DecodingError::UnexpectedEof);
+    }
+    Ok(data)
+}
+
+impl From<std::io::Error> for DecodingError {
+    fn from(error: std::io::Error) -> Self {
+        if error.kind() == std::io::ErrorKind::UnexpectedEof {
+            Self::UnexpectedEof
+        } else {
+            Self::Io(error)
+        }
+    }
+}
+
+impl BinaryImage {
+    /// Number of bytes needed to hold one row at one bit per pixel.
+    #[inline]
+    pub(crate) fn packed_row_len(&self) -> usize {
+        (self.width as usize).div_ceil(8)
+    }
+
+    /// Packs row `y` into `bytes`, see [`bits::pack`].
+    pub(crate) fn pack_row(&self, y: u32, bytes: &mut [u8], msb_first: bool, invert: bool) {
+        let offset = self.row_offset(y);
+        bits::pack(
+            &self.buffer,
+            offset,
+            self.width as usize,
+            bytes,
+            msb_first,
+            invert,
+        );
+    }
+
+    /// Adds `rows` clear rows at the bottom, for decoders that grow an image
+    /// as its data arrives.
+    pub(crate) fn grow(&mut self, rows: u32) {
+        self.buffer.grow(self.width as usize * rows as usize, false);
+        self.height += rows;
+    }
+
+    /// Unpacks row `y` from `bytes`, see [`bits::unpack`].
+    pub(crate) fn unpack_row(&mut self, y: u32, bytes: &[u8], msb_first: bool, invert: bool) {
+        let offset = self.row_offset(y);
+        let width = self.width as usize;
+        bits::unpack(bytes, &mut self.buffer, offset, width, msb_first, invert);
+    }
+}
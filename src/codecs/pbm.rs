@@ -0,0 +1,244 @@
+//! Netpbm PBM, both the plain `P1` and the raw `P4` variant.
+//!
+//! PBM stores black as 1, so set pixels are written as 0 and vice versa.
+//! A stream may hold several images back to back; use [`PbmDecoder`] to read
+//! all of them.
+
+use std::io::{BufRead, Write};
+
+use bit_vec::BitVec;
+
+use super::{read_growing, DecodingError};
+use crate::BinaryImage;
+
+/// Line length limit of the plain format.
+const PLAIN_LINE_LEN: usize = 70;
+
+/// The PBM variant to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PbmFormat {
+    /// `P1`: one ASCII digit per pixel.
+    Ascii,
+    /// `P4`: eight pixels per byte, rows padded to whole bytes.
+    #[default]
+    Raw,
+}
+
+/// Reads consecutive PBM images from a stream.
+#[derive(Debug)]
+pub struct PbmDecoder<R> {
+    reader: R,
+    failed: bool,
+}
+
+impl<R: BufRead> PbmDecoder<R> {
+    #[must_use]
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            failed: false,
+        }
+    }
+
+    /// Decodes the next image, or returns `None` at the end of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header or raster is malformed or truncated.
+    pub fn next_image(&mut self) -> Result<Option<BinaryImage>, DecodingError> {
+        skip_separators(&mut self.reader)?;
+        let Some(magic) = peek(&mut self.reader)? else {
+            return Ok(None);
+        };
+        if magic != b'P' {
+            return Err(DecodingError::InvalidHeader("not a PBM file".into()));
+        }
+        self.reader.consume(1);
+        let format = match next(&mut self.reader)? {
+            b'1' => PbmFormat::Ascii,
+            b'4' => PbmFormat::Raw,
+            digit @ b'2'..=b'7' => {
+                return Err(DecodingError::Unsupported(format!(
+                    "P{} is not a bitmap format",
+                    char::from(digit)
+                )))
+            }
+            _ => return Err(DecodingError::InvalidHeader("not a PBM file".into())),
+        };
+        let width = read_number(&mut self.reader)?;
+        let height = read_number(&mut self.reader)?;
+        if width.checked_mul(height).is_none() {
+            return Err(DecodingError::InvalidHeader("image too large".into()));
+        }
+        // The raw raster starts after exactly one whitespace character.
+        if format == PbmFormat::Raw && !next(&mut self.reader)?.is_ascii_whitespace() {
+            return Err(DecodingError::InvalidHeader(
+                "expected whitespace after height".into(),
+            ));
+        }
+
+        // Grow the raster as it arrives rather than trusting the header.
+        let image = match format {
+            PbmFormat::Ascii => {
+                let mut buffer = BitVec::new();
+                for _ in 0..u64::from(width) * u64::from(height) {
+                    match next_significant(&mut self.reader)? {
+                        b'0' => buffer.push(true),
+                        b'1' => buffer.push(false),
+                        byte => {
+                            return Err(DecodingError::InvalidData(format!(
+                                "unexpected {:?} in raster",
+                                char::from(byte)
+                            )))
+                        }
+                    }
+                }
+                BinaryImage::from_bitvec(width, height, buffer)
+            }
+            PbmFormat::Raw => {
+                let row_len = (width as usize).div_ceil(8);
+                let data = read_growing(&mut self.reader, row_len as u64 * u64::from(height))?;
+                let mut image = BinaryImage::new(width, height);
+                for (y, row) in (0..height).zip(data.chunks(row_len.max(1))) {
+                    image.unpack_row(y, row, true, true);
+                }
+                image
+            }
+        };
+        Ok(Some(image))
+    }
+
+    /// Returns the underlying reader, positioned after the last decoded image.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> Iterator for PbmDecoder<R> {
+    type Item = Result<BinaryImage, DecodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.next_image().transpose();
+        self.failed = matches!(result, Some(Err(_)));
+        result
+    }
+}
+
+/// Decodes the first PBM image in `reader`.
+///
+/// # Errors
+///
+/// Returns an error if the stream is empty, or if the header or raster is
+/// malformed or truncated.
+pub fn decode<R: BufRead>(reader: R) -> Result<BinaryImage, DecodingError> {
+    PbmDecoder::new(reader)
+        .next_image()?
+        .ok_or(DecodingError::UnexpectedEof)
+}
+
+/// Encodes `image` as PBM.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+pub fn encode<W: Write>(
+    image: &BinaryImage,
+    mut writer: W,
+    format: PbmFormat,
+) -> std::io::Result<()> {
+    let (width, height) = (image.width(), image.height());
+    match format {
+        PbmFormat::Ascii => {
+            writeln!(writer, "P1\n{width} {height}")?;
+            let mut line = Vec::with_capacity(PLAIN_LINE_LEN + 1);
+            for y in 0..height {
+                let offset = image.row_offset(y);
+                for x in 0..width as usize {
+                    line.push(if image.buffer[offset + x] { b'0' } else { b'1' });
+                    if line.len() == PLAIN_LINE_LEN {
+                        line.push(b'\n');
+                        writer.write_all(&line)?;
+                        line.clear();
+                    }
+                }
+                if !line.is_empty() {
+                    line.push(b'\n');
+                    writer.write_all(&line)?;
+                    line.clear();
+                }
+            }
+        }
+        PbmFormat::Raw => {
+            writeln!(writer, "P4\n{width} {height}")?;
+            let mut row = vec![0; image.packed_row_len()];
+            for y in 0..height {
+                image.pack_row(y, &mut row, true, true);
+                writer.write_all(&row)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl BinaryImage {
+    /// Encodes the image as PBM, see [`encode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_pbm<W: Write>(&self, writer: W, format: PbmFormat) -> std::io::Result<()> {
+        encode(self, writer, format)
+    }
+}
+
+fn peek<R: BufRead>(reader: &mut R) -> Result<Option<u8>, DecodingError> {
+    Ok(reader.fill_buf()?.first().copied())
+}
+
+fn next<R: BufRead>(reader: &mut R) -> Result<u8, DecodingError> {
+    let byte = peek(reader)?.ok_or(DecodingError::UnexpectedEof)?;
+    reader.consume(1);
+    Ok(byte)
+}
+
+/// Skips whitespace and `#` comments, which run to the end of the line.
+fn skip_separators<R: BufRead>(reader: &mut R) -> Result<(), DecodingError> {
+    while let Some(byte) = peek(reader)? {
+        if byte == b'#' {
+            let mut comment = Vec::new();
+            reader.read_until(b'\n', &mut comment)?;
+        } else if byte.is_ascii_whitespace() {
+            reader.consume(1);
+        } else {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn next_significant<R: BufRead>(reader: &mut R) -> Result<u8, DecodingError> {
+    skip_separators(reader)?;
+    next(reader)
+}
+
+fn read_number<R: BufRead>(reader: &mut R) -> Result<u32, DecodingError> {
+    skip_separators(reader)?;
+    let mut value: u32 = 0;
+    let mut digits = 0;
+    while let Some(byte @ b'0'..=b'9') = peek(reader)? {
+        reader.consume(1);
+        digits += 1;
+        value = value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(u32::from(byte - b'0')))
+            .ok_or_else(|| DecodingError::InvalidHeader("dimension too large".into()))?;
+    }
+    match peek(reader)? {
+        None => Err(DecodingError::UnexpectedEof),
+        Some(byte) if digits > 0 && (byte.is_ascii_whitespace() || byte == b'#') => Ok(value),
+        Some(_) => Err(DecodingError::InvalidHeader("expected a dimension".into())),
+    }
+}
@@ -0,0 +1,114 @@
+//! 1-bit grayscale PNG, backed by the [`png`](::png) crate.
+//!
+//! Rows are handed to the compressor in packed form, eight pixels per byte,
+//! so no 8-bit intermediate image is created in either direction.
+
+use std::io::{BufRead, Seek, Write};
+
+use ::png::{BitDepth, ColorType, Transformations};
+
+use super::DecodingError;
+use crate::{bits, BinaryImage, Bit};
+
+impl From<::png::DecodingError> for DecodingError {
+    fn from(error: ::png::DecodingError) -> Self {
+        match error {
+            ::png::DecodingError::IoError(error) => error.into(),
+            ::png::DecodingError::LimitsExceeded => Self::Unsupported("image too large".into()),
+            error => Self::InvalidData(error.to_string()),
+        }
+    }
+}
+
+/// Decodes a PNG with 1-bit grayscale or 1-bit indexed pixels.
+///
+/// Gray 1 is white and therefore set; palette colors map to set pixels if any
+/// of their channels is nonzero.
+///
+/// # Errors
+///
+/// Returns an error if the data is not a valid PNG or uses a bit depth other
+/// than 1.
+pub fn decode<R: BufRead + Seek>(reader: R) -> Result<BinaryImage, DecodingError> {
+    let mut decoder = ::png::Decoder::new(reader);
+    decoder.set_transformations(Transformations::IDENTITY);
+    let mut reader = decoder.read_info()?;
+
+    let info = reader.info();
+    if info.bit_depth != BitDepth::One {
+        return Err(DecodingError::Unsupported(format!(
+            "bit depth {:?}",
+            info.bit_depth
+        )));
+    }
+    let (zero, one) = match info.color_type {
+        ColorType::Grayscale => (Bit(false), Bit(true)),
+        ColorType::Indexed => {
+            let palette = info.palette.as_deref().unwrap_or_default();
+            let color = |index: usize| {
+                palette
+                    .chunks_exact(3)
+                    .nth(index)
+                    .map_or(Bit(false), |rgb| {
+                        Bit::from(image::Rgb([rgb[0], rgb[1], rgb[2]]))
+                    })
+            };
+            (color(0), color(1))
+        }
+        color_type => {
+            return Err(DecodingError::Unsupported(format!(
+                "color type {color_type:?}"
+            )))
+        }
+    };
+    let (width, height) = (info.width, info.height);
+
+    let len = reader
+        .output_buffer_size()
+        .ok_or_else(|| DecodingError::Unsupported("image too large".into()))?;
+    let mut buffer = vec![0; len];
+    let output = reader.next_frame(&mut buffer)?;
+
+    let mut image = BinaryImage::new(width, height);
+    if zero == one {
+        let len = image.buffer.len();
+        bits::fill(&mut image.buffer, 0, len, *zero);
+        return Ok(image);
+    }
+    for (y, row) in (0..height).zip(buffer.chunks(output.line_size)) {
+        image.unpack_row(y, row, true, *zero);
+    }
+    Ok(image)
+}
+
+/// Encodes `image` as a 1-bit grayscale PNG.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+pub fn encode<W: Write>(image: &BinaryImage, writer: W) -> std::io::Result<()> {
+    let mut encoder = ::png::Encoder::new(writer, image.width(), image.height());
+    encoder.set_color(ColorType::Grayscale);
+    encoder.set_depth(BitDepth::One);
+    let mut writer = encoder.write_header()?;
+    let mut stream = writer.stream_writer()?;
+
+    let mut row = vec![0; image.packed_row_len()];
+    for y in 0..image.height() {
+        image.pack_row(y, &mut row, true, false);
+        stream.write_all(&row)?;
+    }
+    stream.finish()?;
+    Ok(())
+}
+
+impl BinaryImage {
+    /// Encodes the image as a 1-bit grayscale PNG, see [`encode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_png<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        encode(self, writer)
+    }
+}
@@ -0,0 +1,213 @@
+//! SVG output of the set pixels as vector paths.
+//!
+//! Each path holds the outlines traced by [`BinaryImage::outlines`] as
+//! subpaths and is filled with the even-odd rule, so holes stay clear. One
+//! unit of the SVG coordinate system is one pixel. [`encode_traced`] writes
+//! the curves of [`BinaryImage::trace`] instead.
+
+use std::fmt::Write;
+
+use crate::{BinaryImage, Connectivity, Outline, TraceOptions};
+
+/// How outlines are turned into path data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PathStyle {
+    /// Straight lines between the midpoints of the pixel edges next to each
+    /// corner, so one-pixel steps become diagonals.
+    #[default]
+    Polygon,
+    /// Like `Polygon`, with each corner rounded by a quadratic curve.
+    Smooth,
+    /// The exact pixel edges, with horizontal and vertical lines only.
+    Staircase,
+}
+
+/// Options for [`encode`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SvgOptions {
+    pub style: PathStyle,
+    /// Whether pixels touching only at a corner belong to the same outline
+    /// and component.
+    pub connectivity: Connectivity,
+    /// Emit one `<g>` per connected component, with id `{id_prefix}{label}`
+    /// and labels as in [`BinaryImage::components`], instead of a single
+    /// path.
+    pub group_components: bool,
+    pub id_prefix: String,
+    /// Fill color of the paths.
+    pub fill: String,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            style: PathStyle::default(),
+            connectivity: Connectivity::Eight,
+            group_components: false,
+            id_prefix: "component-".into(),
+            fill: "black".into(),
+        }
+    }
+}
+
+/// Generates an SVG document drawing the set pixels of `image`.
+#[must_use]
+pub fn encode(image: &BinaryImage, options: &SvgOptions) -> String {
+    let subpaths = image
+        .outlines(options.connectivity)
+        .iter()
+        .map(|outline| {
+            let mut data = String::new();
+            path_data(&mut data, outline, options.style);
+            (outline.inner_pixel(), data)
+        })
+        .collect();
+    document(image, options.connectivity, options, subpaths)
+}
+
+/// Generates an SVG document drawing the paths traced from `image` by
+/// [`BinaryImage::trace`].
+///
+/// `style` and `connectivity` of `options` are ignored in favor of
+/// `trace_options`.
+#[must_use]
+pub fn encode_traced(
+    image: &BinaryImage,
+    options: &SvgOptions,
+    trace_options: &TraceOptions,
+) -> String {
+    let subpaths = image
+        .trace(trace_options)
+        .iter()
+        .map(|path| (path.pixel, path.svg_data()))
+        .collect();
+    document(image, trace_options.connectivity, options, subpaths)
+}
+
+impl BinaryImage {
+    /// Generates an SVG document drawing the set pixels, see [`encode`].
+    #[must_use]
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        encode(self, options)
+    }
+}
+
+/// Writes the document around closed subpaths, each given with a set pixel
+/// it borders.
+fn document(
+    image: &BinaryImage,
+    connectivity: Connectivity,
+    options: &SvgOptions,
+    subpaths: Vec<((u32, u32), String)>,
+) -> String {
+    let (width, height) = (image.width(), image.height());
+    let mut svg = String::new();
+    // Writing to a `String` cannot fail.
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    let fill = escape(&options.fill);
+    if options.group_components {
+        let components = image.components(connectivity);
+        let mut groups = vec![Vec::new(); components.count() as usize];
+        for ((x, y), data) in subpaths {
+            groups[components.label(x, y) as usize - 1].push(data);
+        }
+        let prefix = escape(&options.id_prefix);
+        for (label, group) in (1..).zip(&groups) {
+            let data = group.join(" ");
+            let _ = writeln!(
+                svg,
+                r#"<g id="{prefix}{label}"><path fill="{fill}" fill-rule="evenodd" d="{data}"/></g>"#
+            );
+        }
+    } else if !subpaths.is_empty() {
+        let data: Vec<_> = subpaths.into_iter().map(|(_, data)| data).collect();
+        let data = data.join(" ");
+        let _ = writeln!(
+            svg,
+            r#"<path fill="{fill}" fill-rule="evenodd" d="{data}"/>"#
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Appends `outline` as a closed subpath.
+fn path_data(data: &mut String, outline: &Outline, style: PathStyle) {
+    let points = &outline.points;
+    if style == PathStyle::Staircase {
+        let (mut x, y) = points[0];
+        let _ = write!(data, "M{x} {y}");
+        // Outlines alternate between horizontal and vertical edges.
+        for &(next_x, next_y) in &points[1..] {
+            if next_x == x {
+                let _ = write!(data, "V{next_y}");
+            } else {
+                let _ = write!(data, "H{next_x}");
+            }
+            x = next_x;
+        }
+        data.push('Z');
+        return;
+    }
+
+    // Corners in half pixels, each with the points half a pixel before and
+    // after it along the outline.
+    let n = points.len();
+    let double = |(x, y): (u32, u32)| (i64::from(x) * 2, i64::from(y) * 2);
+    let toward = |(x, y): (i64, i64), (to_x, to_y): (i64, i64)| {
+        (x + (to_x - x).signum(), y + (to_y - y).signum())
+    };
+    let corners: Vec<_> = (0..n)
+        .map(|i| {
+            let corner = double(points[i]);
+            let before = toward(corner, double(points[(i + n - 1) % n]));
+            let after = toward(corner, double(points[(i + 1) % n]));
+            (before, corner, after)
+        })
+        .collect();
+    let half = |value: i64| {
+        #[allow(clippy::cast_precision_loss)]
+        let value = value as f64 / 2.0;
+        value
+    };
+    let (_, _, start) = corners[0];
+    let _ = write!(data, "M{} {}", half(start.0), half(start.1));
+    let mut position = start;
+    for i in 1..=n {
+        let (before, corner, after) = corners[i % n];
+        if before != position {
+            let _ = write!(data, "L{} {}", half(before.0), half(before.1));
+        }
+        if style == PathStyle::Smooth {
+            let _ = write!(
+                data,
+                "Q{} {} {} {}",
+                half(corner.0),
+                half(corner.1),
+                half(after.0),
+                half(after.1)
+            );
+        } else if i < n {
+            let _ = write!(data, "L{} {}", half(after.0), half(after.1));
+        }
+        position = after;
+    }
+    data.push('Z');
+}
+
+/// Escapes text for an attribute value.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,645 @@
+//! Bilevel TIFF, single- and multi-page.
+//!
+//! Pages must have one sample of one bit per pixel, stored in strips. The
+//! strips may be uncompressed or compressed with `PackBits` or CCITT fax coding
+//! (TIFF compression 2, 3 and 4), which is handled by [`ccitt`](super::ccitt).
+//! Both `WhiteIsZero` and `BlackIsZero` photometric interpretations and both
+//! fill orders are read; files are written little-endian as `WhiteIsZero`,
+//! with each page in a single strip.
+#![allow(clippy::cast_possible_truncation)]
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use super::ccitt::{self, CcittOptions};
+use super::DecodingError;
+use crate::{bits, BinaryImage};
+
+const IMAGE_WIDTH: u16 = 256;
+const IMAGE_LENGTH: u16 = 257;
+const BITS_PER_SAMPLE: u16 = 258;
+const COMPRESSION: u16 = 259;
+const PHOTOMETRIC: u16 = 262;
+const FILL_ORDER: u16 = 266;
+const STRIP_OFFSETS: u16 = 273;
+const SAMPLES_PER_PIXEL: u16 = 277;
+const ROWS_PER_STRIP: u16 = 278;
+const STRIP_BYTE_COUNTS: u16 = 279;
+const X_RESOLUTION: u16 = 282;
+const Y_RESOLUTION: u16 = 283;
+const T4_OPTIONS: u16 = 292;
+const T6_OPTIONS: u16 = 293;
+const RESOLUTION_UNIT: u16 = 296;
+const TILE_WIDTH: u16 = 322;
+const NEW_SUBFILE_TYPE: u16 = 254;
+
+const SHORT: u16 = 3;
+const LONG: u16 = 4;
+const RATIONAL: u16 = 5;
+
+/// Compression scheme of written pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TiffCompression {
+    /// No compression, rows padded to whole bytes.
+    None,
+    /// Byte-oriented run-length coding of each row.
+    PackBits,
+    /// Modified Huffman coding without end-of-line codes (compression 2).
+    CcittRle,
+    /// CCITT Group 3 coding with end-of-line codes, one- or
+    /// two-dimensional (compression 3).
+    Group3 { two_dimensional: bool },
+    /// CCITT Group 4 coding (compression 4).
+    #[default]
+    Group4,
+}
+
+/// Unit of a [`Resolution`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ResolutionUnit {
+    /// Only the aspect ratio is meaningful.
+    Unitless,
+    #[default]
+    Inch,
+    Centimeter,
+}
+
+/// Pixels per unit, e.g. the DPI of a scanned page.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resolution {
+    pub x: f64,
+    pub y: f64,
+    pub unit: ResolutionUnit,
+}
+
+/// A page of a TIFF file.
+#[derive(Debug, Clone)]
+pub struct TiffPage {
+    pub image: BinaryImage,
+    pub resolution: Option<Resolution>,
+}
+
+/// Decodes all pages of a bilevel TIFF file.
+///
+/// The file is read into memory completely. Reduced-resolution subfiles such
+/// as thumbnails are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the file is not a TIFF, is truncated or malformed, or
+/// holds a page that is not a bilevel image in strips.
+pub fn decode<R: Read>(mut reader: R) -> Result<Vec<TiffPage>, DecodingError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    let big_endian = match data.get(..4) {
+        Some(b"II*\0") => false,
+        Some(b"MM\0*") => true,
+        Some(b"II+\0" | b"MM\0+") => {
+            return Err(DecodingError::Unsupported("BigTIFF".into()));
+        }
+        Some(_) => return Err(DecodingError::InvalidHeader("not a TIFF file".into())),
+        None => return Err(DecodingError::UnexpectedEof),
+    };
+    let file = File {
+        data: &data,
+        big_endian,
+    };
+
+    let mut pages = Vec::new();
+    let mut visited = Vec::new();
+    let mut offset = file.u32_at(4)?;
+    while offset != 0 {
+        if visited.contains(&offset) {
+            return Err(DecodingError::InvalidHeader("IFD loop".into()));
+        }
+        visited.push(offset);
+        let (ifd, next) = file.ifd(offset as usize)?;
+        if ifd.value(NEW_SUBFILE_TYPE)?.unwrap_or(0) & 1 == 0 {
+            pages.push(ifd.page()?);
+        }
+        offset = next;
+    }
+    Ok(pages)
+}
+
+/// Encodes `pages` as a multi-page TIFF file.
+///
+/// # Errors
+///
+/// Returns an error if writing fails or the file would exceed 4 GiB.
+pub fn encode<W: Write>(
+    pages: &[TiffPage],
+    mut writer: W,
+    compression: TiffCompression,
+) -> std::io::Result<()> {
+    let too_large =
+        || std::io::Error::new(std::io::ErrorKind::InvalidInput, "image too large for TIFF");
+    let mut out = b"II*\0\0\0\0\0".to_vec();
+    // Where to patch in the offset of the next IFD.
+    let mut link = 4;
+
+    for page in pages {
+        let (width, height) = (page.image.width(), page.image.height());
+        let strip = encode_strip(&page.image, compression);
+        let (code, t4_options) = match compression {
+            TiffCompression::None => (1, None),
+            TiffCompression::PackBits => (32773, None),
+            TiffCompression::CcittRle => (2, None),
+            TiffCompression::Group3 { two_dimensional } => (3, Some(u32::from(two_dimensional))),
+            TiffCompression::Group4 => (4, None),
+        };
+
+        let strip_offset = u32::try_from(out.len()).map_err(|_| too_large())?;
+        let strip_len = u32::try_from(strip.len()).map_err(|_| too_large())?;
+        out.extend_from_slice(&strip);
+        // IFDs start on a word boundary.
+        if out.len() % 2 == 1 {
+            out.push(0);
+        }
+
+        let mut entries = vec![
+            (IMAGE_WIDTH, LONG, width),
+            (IMAGE_LENGTH, LONG, height),
+            (BITS_PER_SAMPLE, SHORT, 1),
+            (COMPRESSION, SHORT, code),
+            (PHOTOMETRIC, SHORT, 0),
+            (STRIP_OFFSETS, LONG, strip_offset),
+            (SAMPLES_PER_PIXEL, SHORT, 1),
+            (ROWS_PER_STRIP, LONG, height),
+            (STRIP_BYTE_COUNTS, LONG, strip_len),
+        ];
+        let mut rationals = Vec::new();
+        if let Some(resolution) = page.resolution {
+            entries.push((X_RESOLUTION, RATIONAL, 0));
+            entries.push((Y_RESOLUTION, RATIONAL, 0));
+            rationals.extend([to_rational(resolution.x), to_rational(resolution.y)]);
+        }
+        if let Some(options) = t4_options {
+            entries.push((T4_OPTIONS, LONG, options));
+        }
+        if let Some(resolution) = page.resolution {
+            let unit = match resolution.unit {
+                ResolutionUnit::Unitless => 1,
+                ResolutionUnit::Inch => 2,
+                ResolutionUnit::Centimeter => 3,
+            };
+            entries.push((RESOLUTION_UNIT, SHORT, unit));
+        }
+
+        let ifd_offset = u32::try_from(out.len()).map_err(|_| too_large())?;
+        out[link..link + 4].copy_from_slice(&ifd_offset.to_le_bytes());
+        let ifd_len = 2 + 12 * entries.len() + 4;
+        let mut rational_offset = ifd_offset as usize + ifd_len;
+        out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (tag, kind, value) in entries {
+            out.extend_from_slice(&tag.to_le_bytes());
+            out.extend_from_slice(&kind.to_le_bytes());
+            out.extend_from_slice(&1u32.to_le_bytes());
+            match kind {
+                SHORT => out.extend_from_slice(&[value as u8, (value >> 8) as u8, 0, 0]),
+                RATIONAL => {
+                    let offset = u32::try_from(rational_offset).map_err(|_| too_large())?;
+                    out.extend_from_slice(&offset.to_le_bytes());
+                    rational_offset += 8;
+                }
+                _ => out.extend_from_slice(&value.to_le_bytes()),
+            }
+        }
+        link = out.len();
+        out.extend_from_slice(&[0; 4]);
+        for (numerator, denominator) in rationals {
+            out.extend_from_slice(&numerator.to_le_bytes());
+            out.extend_from_slice(&denominator.to_le_bytes());
+        }
+    }
+    u32::try_from(out.len()).map_err(|_| too_large())?;
+    writer.write_all(&out)
+}
+
+impl BinaryImage {
+    /// Encodes the image as a single-page TIFF without resolution, see
+    /// [`encode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails or the file would exceed 4 GiB.
+    pub fn write_tiff<W: Write>(
+        &self,
+        writer: W,
+        compression: TiffCompression,
+    ) -> std::io::Result<()> {
+        let page = TiffPage {
+            image: self.clone(),
+            resolution: None,
+        };
+        encode(std::slice::from_ref(&page), writer, compression)
+    }
+}
+
+/// Compresses all rows of `image` as `WhiteIsZero` samples.
+fn encode_strip(image: &BinaryImage, compression: TiffCompression) -> Vec<u8> {
+    let ccitt = |options| ccitt::encode(image, &options);
+    match compression {
+        TiffCompression::None | TiffCompression::PackBits => {
+            let mut row = vec![0; image.packed_row_len()];
+            let mut strip = Vec::with_capacity(row.len() * image.height() as usize);
+            for y in 0..image.height() {
+                image.pack_row(y, &mut row, true, true);
+                if compression == TiffCompression::PackBits {
+                    pack_bits(&row, &mut strip);
+                } else {
+                    strip.extend_from_slice(&row);
+                }
+            }
+            strip
+        }
+        TiffCompression::CcittRle => ccitt(CcittOptions {
+            encoded_byte_align: true,
+            end_of_block: false,
+            ..CcittOptions::default()
+        }),
+        TiffCompression::Group3 { two_dimensional } => {
+            // At most two consecutive two-dimensional rows, as recommended
+            // for standard resolution.
+            ccitt(CcittOptions::group3(if two_dimensional { 2 } else { 0 }))
+        }
+        TiffCompression::Group4 => ccitt(CcittOptions::group4()),
+    }
+}
+
+/// Converts to a fraction, exact for whole numbers.
+fn to_rational(value: f64) -> (u32, u32) {
+    #[allow(clippy::cast_sign_loss)]
+    let fraction = |denominator: u32| (value * f64::from(denominator)).round() as u32;
+    if value.fract() == 0.0 {
+        (fraction(1), 1)
+    } else {
+        (fraction(10_000), 10_000)
+    }
+}
+
+/// Appends the `PackBits` encoding of `bytes` to `out`.
+pub(crate) fn pack_bits(bytes: &[u8], out: &mut Vec<u8>) {
+    let run_at = |i: usize| {
+        bytes[i..]
+            .iter()
+            .take(128)
+            .take_while(|&&byte| byte == bytes[i])
+            .count()
+    };
+    let mut i = 0;
+    while i < bytes.len() {
+        let run = run_at(i);
+        if run >= 3 {
+            out.extend_from_slice(&[(257 - run) as u8, bytes[i]]);
+            i += run;
+            continue;
+        }
+        // Literal bytes up to the next run of three.
+        let start = i;
+        while i < bytes.len() && i - start < 128 && (i == start || run_at(i) < 3) {
+            i += 1;
+        }
+        out.push((i - start - 1) as u8);
+        out.extend_from_slice(&bytes[start..i]);
+    }
+}
+
+/// Decodes `PackBits` data until `out` holds `len` bytes.
+pub(crate) fn unpack_bits(data: &[u8], len: usize, out: &mut Vec<u8>) -> Result<(), DecodingError> {
+    let end = out.len() + len;
+    let mut data = data.iter();
+    while out.len() < end {
+        let header = data
+            .next()
+            .ok_or(DecodingError::UnexpectedEof)?
+            .cast_signed();
+        match header {
+            0..=127 => {
+                for _ in 0..=header {
+                    out.push(*data.next().ok_or(DecodingError::UnexpectedEof)?);
+                }
+            }
+            -127..=-1 => {
+                let byte = *data.next().ok_or(DecodingError::UnexpectedEof)?;
+                out.extend(std::iter::repeat_n(
+                    byte,
+                    1 + header.unsigned_abs() as usize,
+                ));
+            }
+            -128 => {}
+        }
+    }
+    if out.len() > end {
+        return Err(DecodingError::InvalidData(
+            "run crosses row boundary".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// The raw file with its byte order.
+struct File<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> File<'a> {
+    fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8], DecodingError> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or(DecodingError::UnexpectedEof)
+    }
+
+    fn u16_at(&self, offset: usize) -> Result<u16, DecodingError> {
+        let bytes = self.bytes(offset, 2)?.try_into().unwrap_or_default();
+        Ok(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn u32_at(&self, offset: usize) -> Result<u32, DecodingError> {
+        let bytes = self.bytes(offset, 4)?.try_into().unwrap_or_default();
+        Ok(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    /// Parses the IFD at `offset`, returning it and the offset of the next.
+    fn ifd(&self, offset: usize) -> Result<(Ifd<'_, 'a>, u32), DecodingError> {
+        let count = usize::from(self.u16_at(offset)?);
+        let mut entries = HashMap::with_capacity(count);
+        for i in 0..count {
+            let entry = offset + 2 + 12 * i;
+            entries.insert(
+                self.u16_at(entry)?,
+                (self.u16_at(entry + 2)?, self.u32_at(entry + 4)?, entry + 8),
+            );
+        }
+        let next = self.u32_at(offset + 2 + 12 * count)?;
+        Ok((
+            Ifd {
+                file: self,
+                entries,
+            },
+            next,
+        ))
+    }
+}
+
+/// An image file directory: tag to type, count and position of the value.
+struct Ifd<'f, 'a> {
+    file: &'f File<'a>,
+    entries: HashMap<u16, (u16, u32, usize)>,
+}
+
+impl Ifd<'_, '_> {
+    /// Integer values of `tag`.
+    fn values(&self, tag: u16) -> Result<Option<Vec<u32>>, DecodingError> {
+        let Some(&(kind, count, position)) = self.entries.get(&tag) else {
+            return Ok(None);
+        };
+        let size = match kind {
+            1 => 1,
+            SHORT => 2,
+            LONG => 4,
+            _ => {
+                return Err(DecodingError::InvalidHeader(format!(
+                    "unexpected type {kind} of tag {tag}"
+                )))
+            }
+        };
+        let len = count as usize * size;
+        let start = if len <= 4 {
+            position
+        } else {
+            self.file.u32_at(position)? as usize
+        };
+        self.file.bytes(start, len)?;
+        (0..count as usize)
+            .map(|i| match size {
+                1 => Ok(u32::from(self.file.data[start + i])),
+                2 => self.file.u16_at(start + 2 * i).map(u32::from),
+                _ => self.file.u32_at(start + 4 * i),
+            })
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+
+    /// The first integer value of `tag`.
+    fn value(&self, tag: u16) -> Result<Option<u32>, DecodingError> {
+        Ok(self.values(tag)?.and_then(|values| values.first().copied()))
+    }
+
+    fn rational(&self, tag: u16) -> Result<Option<f64>, DecodingError> {
+        let Some(&(kind, _, position)) = self.entries.get(&tag) else {
+            return Ok(None);
+        };
+        if kind != RATIONAL {
+            return Ok(self.value(tag)?.map(f64::from));
+        }
+        let offset = self.file.u32_at(position)? as usize;
+        let numerator = self.file.u32_at(offset)?;
+        let denominator = self.file.u32_at(offset + 4)?;
+        Ok((denominator != 0).then(|| f64::from(numerator) / f64::from(denominator)))
+    }
+
+    /// Offsets and byte counts of the strips covering `height` rows, and the
+    /// rows in each.
+    fn strips(
+        &self,
+        codec: Codec,
+        width: u32,
+        height: u32,
+    ) -> Result<(Vec<u32>, Vec<u32>, u32), DecodingError> {
+        let mut offsets = self
+            .values(STRIP_OFFSETS)?
+            .ok_or_else(|| DecodingError::InvalidHeader("missing strip offsets".into()))?;
+        let rows_per_strip = self.value(ROWS_PER_STRIP)?.unwrap_or(u32::MAX).max(1);
+        let mut counts = match self.values(STRIP_BYTE_COUNTS)? {
+            Some(counts) => counts,
+            // Only uncompressed data has a known size.
+            None if codec == Codec::Raw => {
+                let count = u32::try_from(
+                    (width as usize).div_ceil(8) * rows_per_strip.min(height) as usize,
+                )
+                .map_err(|_| DecodingError::InvalidHeader("strip too large".into()))?;
+                vec![count; offsets.len()]
+            }
+            None => {
+                return Err(DecodingError::InvalidHeader(
+                    "missing strip byte counts".into(),
+                ))
+            }
+        };
+        let strips = height.div_ceil(rows_per_strip) as usize;
+        if offsets.len() < strips || counts.len() < strips {
+            return Err(DecodingError::InvalidHeader("missing strips".into()));
+        }
+
+        offsets.truncate(strips);
+        counts.truncate(strips);
+
+        // The strips must be in the file before the image is allocated.
+        let total: u64 = counts.iter().map(|&count| u64::from(count)).sum();
+        if total > self.file.data.len() as u64 {
+            return Err(DecodingError::UnexpectedEof);
+        }
+        Ok((offsets, counts, rows_per_strip))
+    }
+
+    fn page(&self) -> Result<TiffPage, DecodingError> {
+        let required = |tag: u16, name: &str| {
+            self.value(tag)?
+                .ok_or_else(|| DecodingError::InvalidHeader(format!("missing {name}")))
+        };
+        let width = required(IMAGE_WIDTH, "width")?;
+        let height = required(IMAGE_LENGTH, "height")?;
+        if width.checked_mul(height).is_none() {
+            return Err(DecodingError::InvalidHeader("image too large".into()));
+        }
+        if self.entries.contains_key(&TILE_WIDTH) {
+            return Err(DecodingError::Unsupported("tiled images".into()));
+        }
+        let bits_per_sample = self.value(BITS_PER_SAMPLE)?.unwrap_or(1);
+        let samples = self.value(SAMPLES_PER_PIXEL)?.unwrap_or(1);
+        if (bits_per_sample, samples) != (1, 1) {
+            return Err(DecodingError::Unsupported(format!(
+                "{samples} samples of {bits_per_sample} bits per pixel"
+            )));
+        }
+        let white_is_zero = match required(PHOTOMETRIC, "photometric interpretation")? {
+            0 => true,
+            1 => false,
+            photometric => {
+                return Err(DecodingError::Unsupported(format!(
+                    "photometric interpretation {photometric}"
+                )))
+            }
+        };
+        let reversed = match self.value(FILL_ORDER)?.unwrap_or(1) {
+            1 => false,
+            2 => true,
+            order => {
+                return Err(DecodingError::InvalidHeader(format!("fill order {order}")));
+            }
+        };
+
+        let codec = self.codec()?;
+
+        let (offsets, counts, rows_per_strip) = self.strips(codec, width, height)?;
+        let row_len = (width as usize).div_ceil(8);
+        // The image grows strip by strip as they decode.
+        let mut image = BinaryImage::new(width, 0);
+        let mut reversed_strip = Vec::new();
+        let mut raw = Vec::new();
+        for (i, (&offset, &count)) in offsets.iter().zip(&counts).enumerate() {
+            let mut strip = self.file.bytes(offset as usize, count as usize)?;
+            if reversed {
+                reversed_strip.clear();
+                reversed_strip.extend(strip.iter().map(|byte| byte.reverse_bits()));
+                strip = &reversed_strip;
+            }
+            let top = i as u32 * rows_per_strip;
+            let rows = rows_per_strip.min(height - top);
+
+            if let Codec::Ccitt(options) = codec {
+                let options = CcittOptions {
+                    black_is_1: !white_is_zero,
+                    ..options
+                };
+                let decoded = ccitt::decode(strip, width, Some(rows), &options)?;
+                image.grow(rows);
+                let offset = image.row_offset(top);
+                bits::copy(
+                    &decoded.buffer,
+                    0,
+                    &mut image.buffer,
+                    offset,
+                    (width * rows) as usize,
+                );
+                continue;
+            }
+            let len = row_len * rows as usize;
+            let samples = if codec == Codec::PackBits {
+                raw.clear();
+                unpack_bits(strip, len, &mut raw)?;
+                &raw[..]
+            } else {
+                strip.get(..len).ok_or(DecodingError::UnexpectedEof)?
+            };
+            image.grow(rows);
+            for (y, row) in (top..).zip(samples.chunks_exact(row_len.max(1)).take(rows as usize)) {
+                image.unpack_row(y, row, true, white_is_zero);
+            }
+        }
+
+        Ok(TiffPage {
+            image,
+            resolution: self.resolution()?,
+        })
+    }
+
+    fn codec(&self) -> Result<Codec, DecodingError> {
+        Ok(match self.value(COMPRESSION)?.unwrap_or(1) {
+            1 => Codec::Raw,
+            32773 => Codec::PackBits,
+            2 => Codec::Ccitt(CcittOptions {
+                encoded_byte_align: true,
+                end_of_block: false,
+                ..CcittOptions::default()
+            }),
+            3 => {
+                let options = self.value(T4_OPTIONS)?.unwrap_or(0);
+                if options & 2 != 0 {
+                    return Err(DecodingError::Unsupported("uncompressed mode".into()));
+                }
+                // Rows are tagged as one- or two-dimensional, so any
+                // positive `k` reads them.
+                Codec::Ccitt(CcittOptions {
+                    k: i32::from(options & 1 != 0),
+                    end_of_line: true,
+                    encoded_byte_align: options & 4 != 0,
+                    end_of_block: false,
+                    ..CcittOptions::default()
+                })
+            }
+            4 => {
+                if self.value(T6_OPTIONS)?.unwrap_or(0) & 2 != 0 {
+                    return Err(DecodingError::Unsupported("uncompressed mode".into()));
+                }
+                Codec::Ccitt(CcittOptions::group4())
+            }
+            compression => {
+                return Err(DecodingError::Unsupported(format!(
+                    "compression {compression}"
+                )))
+            }
+        })
+    }
+
+    fn resolution(&self) -> Result<Option<Resolution>, DecodingError> {
+        let x = self.rational(X_RESOLUTION)?;
+        let y = self.rational(Y_RESOLUTION)?;
+        let unit = match self.value(RESOLUTION_UNIT)?.unwrap_or(2) {
+            1 => ResolutionUnit::Unitless,
+            3 => ResolutionUnit::Centimeter,
+            _ => ResolutionUnit::Inch,
+        };
+        Ok(x.zip(y).map(|(x, y)| Resolution { x, y, unit }))
+    }
+}
+
+/// How the strips of a page are compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Codec {
+    Raw,
+    PackBits,
+    /// CCITT coding, with black runs as 1 in the samples.
+    Ccitt(CcittOptions),
+}
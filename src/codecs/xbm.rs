@@ -0,0 +1,196 @@
+//! XBM, the X11 bitmap C source format used for X11 cursors and icons.
+//!
+//! XBM packs eight pixels per byte with the first pixel in the least
+//! significant bit and stores the foreground (black) as 1, so set pixels are
+//! written as 0. Both the X11 `char` arrays and the older X10 `short` arrays
+//! are read; `char` arrays are written.
+
+use std::fmt::Write;
+
+use super::DecodingError;
+use crate::BinaryImage;
+
+/// Values per line in generated sources, as written by the `bitmap` tool.
+const VALUES_PER_LINE: usize = 12;
+
+/// A decoded XBM file.
+#[derive(Debug, Clone)]
+pub struct Xbm {
+    /// Identifier prefix of the `#define`s and the array, e.g. `cursor` for
+    /// `cursor_width`.
+    pub name: String,
+    pub image: BinaryImage,
+    /// Cursor hotspot from `_x_hot` and `_y_hot`, if both are defined.
+    pub hotspot: Option<(u32, u32)>,
+}
+
+/// Parses XBM source text.
+///
+/// # Errors
+///
+/// Returns an error if the dimensions are missing or the array does not hold
+/// exactly one value per packed unit of the image.
+pub fn decode(source: &str) -> Result<Xbm, DecodingError> {
+    let source = strip_comments(source);
+
+    let (mut name, mut width, mut height, mut x_hot, mut y_hot) = (None, None, None, None, None);
+    for line in source.lines() {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("#define") {
+            continue;
+        }
+        let (Some(ident), Some(value)) = (tokens.next(), tokens.next()) else {
+            continue;
+        };
+        let value = || {
+            parse_number(value)
+                .ok_or_else(|| DecodingError::InvalidHeader(format!("invalid value of {ident}")))
+        };
+        if let Some(prefix) = ident.strip_suffix("_width") {
+            name = Some(prefix.to_owned());
+            width = Some(value()?);
+        } else if ident.ends_with("_height") {
+            height = Some(value()?);
+        } else if ident.ends_with("_x_hot") {
+            x_hot = Some(value()?);
+        } else if ident.ends_with("_y_hot") {
+            y_hot = Some(value()?);
+        }
+    }
+    let missing = |what: &str| DecodingError::InvalidHeader(format!("missing {what}"));
+    let width = width.ok_or_else(|| missing("width"))?;
+    let height = height.ok_or_else(|| missing("height"))?;
+    if width.checked_mul(height).is_none() {
+        return Err(DecodingError::InvalidHeader("image too large".into()));
+    }
+
+    let start = source.find('{').ok_or_else(|| missing("bits array"))?;
+    let end = source[start..]
+        .find('}')
+        .map(|end| start + end)
+        .ok_or(DecodingError::UnexpectedEof)?;
+    let declaration = source[..start].rsplit(';').next().unwrap_or_default();
+    let unit_bytes = if declaration.split_whitespace().any(|token| token == "short") {
+        2
+    } else {
+        1
+    };
+
+    let values = source[start + 1..end]
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            parse_number(value)
+                .filter(|&value| u64::from(value) < 1 << (8 * unit_bytes))
+                .ok_or_else(|| DecodingError::InvalidData(format!("invalid value {value:?}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let row_units = (width as usize).div_ceil(8 * unit_bytes);
+    let expected = row_units * height as usize;
+    if values.len() != expected {
+        return Err(DecodingError::InvalidData(format!(
+            "expected {expected} values, found {}",
+            values.len()
+        )));
+    }
+
+    let mut image = BinaryImage::new(width, height);
+    let mut row = Vec::with_capacity(row_units * unit_bytes);
+    for (y, units) in (0..height).zip(values.chunks(row_units.max(1))) {
+        row.clear();
+        for &unit in units {
+            row.extend_from_slice(&unit.to_le_bytes()[..unit_bytes]);
+        }
+        image.unpack_row(y, &row, false, true);
+    }
+
+    Ok(Xbm {
+        name: name.unwrap_or_default(),
+        image,
+        hotspot: x_hot.zip(y_hot),
+    })
+}
+
+/// Generates XBM source for `image`.
+///
+/// Characters of `name` that are not valid in a C identifier are replaced
+/// with `_`, and a leading digit is prefixed with one.
+#[must_use]
+pub fn encode(image: &BinaryImage, name: &str, hotspot: Option<(u32, u32)>) -> String {
+    let mut name: String = name
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect();
+    if name.is_empty() || name.starts_with(|ch: char| ch.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+
+    let mut source = String::new();
+    // Writing to a `String` cannot fail.
+    let _ = writeln!(source, "#define {name}_width {}", image.width());
+    let _ = writeln!(source, "#define {name}_height {}", image.height());
+    if let Some((x, y)) = hotspot {
+        let _ = writeln!(source, "#define {name}_x_hot {x}");
+        let _ = writeln!(source, "#define {name}_y_hot {y}");
+    }
+    let _ = write!(source, "static unsigned char {name}_bits[] = {{");
+
+    let mut row = vec![0; image.packed_row_len()];
+    let mut count = 0;
+    for y in 0..image.height() {
+        image.pack_row(y, &mut row, false, true);
+        for &byte in &row {
+            let separator = match count {
+                0 => "\n   ",
+                _ if count % VALUES_PER_LINE == 0 => ",\n   ",
+                _ => ", ",
+            };
+            let _ = write!(source, "{separator}0x{byte:02x}");
+            count += 1;
+        }
+    }
+    source.push_str(" };\n");
+    source
+}
+
+impl BinaryImage {
+    /// Generates XBM source for the image, see [`encode`].
+    #[must_use]
+    pub fn to_xbm(&self, name: &str, hotspot: Option<(u32, u32)>) -> String {
+        encode(self, name, hotspot)
+    }
+}
+
+fn parse_number(value: &str) -> Option<u32> {
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Removes `/* */` and `//` comments, keeping line breaks.
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find('/') {
+        out.push_str(&rest[..start]);
+        let comment = &rest[start..];
+        if let Some(body) = comment.strip_prefix("/*") {
+            let end = body.find("*/").map_or(body.len(), |end| end + 2);
+            out.extend(body[..end].chars().filter(|&ch| ch == '\n'));
+            rest = &body[end..];
+        } else if comment.starts_with("//") {
+            rest = &comment[comment.find('\n').unwrap_or(comment.len())..];
+        } else {
+            out.push('/');
+            rest = &comment[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
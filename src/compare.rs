@@ -0,0 +1,181 @@
+use std::fmt::{self, Write};
+use std::hash::{Hash, Hasher};
+
+use image::GenericImageView;
+
+use crate::{bits, BinaryImage};
+
+/// Images are equal if they have the same size and pixels; storage past the
+/// last pixel, as allowed by [`BinaryImage::from_bitvec`], is ignored.
+impl PartialEq for BinaryImage {
+    fn eq(&self, other: &Self) -> bool {
+        let len = self.width as usize * self.height as usize;
+        self.width == other.width
+            && self.height == other.height
+            && bits::blocks(&self.buffer, len).eq(bits::blocks(&other.buffer, len))
+    }
+}
+
+impl Eq for BinaryImage {}
+
+impl Hash for BinaryImage {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.height.hash(state);
+        for block in bits::blocks(&self.buffer, self.width as usize * self.height as usize) {
+            block.hash(state);
+        }
+    }
+}
+
+/// The pixels where two images differ, see [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diff {
+    /// Set where the images differ.
+    pub mask: BinaryImage,
+    /// Pixels set in the actual image but not in the expected one.
+    pub false_positives: u64,
+    /// Pixels set in the expected image but not in the actual one.
+    pub false_negatives: u64,
+}
+
+impl Diff {
+    /// Number of differing pixels.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.false_positives + self.false_negatives
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+}
+
+/// Compares an `actual` image, such as a prediction, with an `expected` one.
+///
+/// # Panics
+///
+/// Panics if the images differ in size.
+#[must_use]
+pub fn diff(actual: &BinaryImage, expected: &BinaryImage) -> Diff {
+    assert_eq!(
+        actual.dimensions(),
+        expected.dimensions(),
+        "Images differ in size"
+    );
+    let len = actual.width as usize * actual.height as usize;
+    let mut mask = BinaryImage::new(actual.width, actual.height);
+    let (mut false_positives, mut false_negatives) = (0, 0);
+    // SAFETY: the blocks are masked to `len` bits, so the unused tail of the
+    // last block stays zeroed as `BitVec` requires.
+    let storage = unsafe { mask.buffer.storage_mut() };
+    for ((block, a), b) in storage
+        .iter_mut()
+        .zip(bits::blocks(&actual.buffer, len))
+        .zip(bits::blocks(&expected.buffer, len))
+    {
+        *block = a ^ b;
+        false_positives += u64::from((a & !b).count_ones());
+        false_negatives += u64::from((!a & b).count_ones());
+    }
+    Diff {
+        mask,
+        false_positives,
+        false_negatives,
+    }
+}
+
+/// Asserts that two images are equal, showing the region where they differ
+/// side by side on failure.
+///
+/// In the region, `#` and `.` are set and clear pixels, and the third column
+/// marks pixels set only on the left with `+` and only on the right with `-`.
+#[macro_export]
+macro_rules! assert_binary_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if left != right {
+                    $crate::__assert_binary_eq_failed(left, right, ::core::option::Option::None);
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if left != right {
+                    $crate::__assert_binary_eq_failed(
+                        left,
+                        right,
+                        ::core::option::Option::Some(::core::format_args!($($arg)+)),
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Largest region shown by [`assert_binary_eq!`](crate::assert_binary_eq), in
+/// pixels along each side.
+const MAX_REGION: u32 = 64;
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_failed(
+    left: &BinaryImage,
+    right: &BinaryImage,
+    message: Option<fmt::Arguments<'_>>,
+) -> ! {
+    let mut text = String::from("assertion `left == right` failed");
+    if let Some(message) = message {
+        let _ = write!(text, ": {message}");
+    }
+    if left.dimensions() != right.dimensions() {
+        let ((left_width, left_height), (right_width, right_height)) =
+            (left.dimensions(), right.dimensions());
+        panic!("{text}\n  left: {left_width}x{left_height}\n right: {right_width}x{right_height}");
+    }
+
+    let diff = diff(left, right);
+    let Some(region) = diff.mask.bounding_box() else {
+        panic!("{text}");
+    };
+    let (x, y) = (region.x, region.y);
+    let (width, height) = (region.width.min(MAX_REGION), region.height.min(MAX_REGION));
+    let _ = writeln!(
+        text,
+        "\n{} pixels differ, {} set only left and {} only right, in columns {}..{} and rows {}..{}{}:",
+        diff.count(),
+        diff.false_positives,
+        diff.false_negatives,
+        region.x,
+        region.x + region.width,
+        region.y,
+        region.y + region.height,
+        if (width, height) == (region.width, region.height) {
+            ""
+        } else {
+            ", showing the top left"
+        }
+    );
+    let pad = (width as usize).max("right".len());
+    let _ = writeln!(text, "{:pad$} | {:pad$} | diff", "left", "right");
+    for row in y..y + height {
+        let mut marks = String::with_capacity(pad);
+        let mut pixels = [String::with_capacity(pad), String::with_capacity(pad)];
+        for column in x..x + width {
+            let (a, b) = (*left.get_pixel(column, row), *right.get_pixel(column, row));
+            pixels[0].push(if a { '#' } else { '.' });
+            pixels[1].push(if b { '#' } else { '.' });
+            marks.push(match (a, b) {
+                (true, false) => '+',
+                (false, true) => '-',
+                _ => '.',
+            });
+        }
+        let _ = writeln!(text, "{:pad$} | {:pad$} | {marks}", pixels[0], pixels[1]);
+    }
+    panic!("{text}");
+}
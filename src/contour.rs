@@ -0,0 +1,174 @@
+use std::cmp::Ordering;
+
+use crate::{bits, BinaryImage};
+
+/// Which neighbors make pixels connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Connectivity {
+    /// Pixels sharing an edge.
+    Four,
+    /// Pixels sharing an edge or a corner.
+    #[default]
+    Eight,
+}
+
+/// A closed outline along the pixel edges between set and clear pixels.
+///
+/// Vertices are pixel corners, so a single pixel at `(x, y)` is outlined by
+/// `(x, y)`, `(x + 1, y)`, `(x + 1, y + 1)` and `(x, y + 1)`. Only corners
+/// where the outline turns are kept, and the first vertex is not repeated at
+/// the end. Set pixels are always on the right when walking along the outline
+/// with `y` pointing down, so outer boundaries run clockwise on screen and
+/// holes counter-clockwise.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Outline {
+    pub points: Vec<(u32, u32)>,
+}
+
+impl Outline {
+    /// Area enclosed by the outline, positive for outer boundaries and
+    /// negative for holes.
+    #[must_use]
+    pub fn signed_area(&self) -> i64 {
+        let twice: i64 = self
+            .points
+            .iter()
+            .zip(self.points.iter().cycle().skip(1))
+            .map(|(&(x0, y0), &(x1, y1))| {
+                i64::from(x0) * i64::from(y1) - i64::from(x1) * i64::from(y0)
+            })
+            .sum();
+        twice / 2
+    }
+
+    /// Whether the outline encloses clear pixels inside a set region.
+    #[must_use]
+    pub fn is_hole(&self) -> bool {
+        self.signed_area() < 0
+    }
+
+    /// A set pixel bordering the outline, such as for looking up the
+    /// component it belongs to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the outline has fewer than two vertices.
+    #[must_use]
+    pub fn inner_pixel(&self) -> (u32, u32) {
+        let ((x0, y0), (x1, y1)) = (self.points[0], self.points[1]);
+        // The pixel right of the first edge.
+        match (x1.cmp(&x0), y1.cmp(&y0)) {
+            (Ordering::Greater, _) => (x0, y0),
+            (_, Ordering::Greater) => (x0 - 1, y0),
+            (Ordering::Less, _) => (x0 - 1, y0 - 1),
+            _ => (x0, y0 - 1),
+        }
+    }
+
+    /// Length of the outline in pixel edges.
+    #[must_use]
+    pub fn perimeter(&self) -> u64 {
+        self.points
+            .iter()
+            .zip(self.points.iter().cycle().skip(1))
+            .map(|(&(x0, y0), &(x1, y1))| u64::from(x0.abs_diff(x1) + y0.abs_diff(y1)))
+            .sum()
+    }
+}
+
+/// Directions of boundary edges, as bits of the per-vertex edge masks.
+const EAST: u8 = 1;
+const SOUTH: u8 = 2;
+const WEST: u8 = 4;
+const NORTH: u8 = 8;
+
+/// Turns `direction` by a quarter, clockwise on screen if `right`.
+fn turn(direction: u8, right: bool) -> u8 {
+    match (right, direction) {
+        (true, NORTH) => EAST,
+        (true, _) => direction << 1,
+        (false, EAST) => NORTH,
+        (false, _) => direction >> 1,
+    }
+}
+
+impl BinaryImage {
+    /// Traces the boundaries between set and clear pixels.
+    ///
+    /// `connectivity` decides how pixels touching only at a corner are
+    /// outlined: with [`Connectivity::Eight`] they share one outline, with
+    /// [`Connectivity::Four`] each gets its own. Either way, filling the
+    /// outlines with the even-odd rule reproduces the image. Outlines are
+    /// ordered by their top-left vertex, so every outer boundary comes before
+    /// the holes inside it.
+    #[must_use]
+    pub fn outlines(&self, connectivity: Connectivity) -> Vec<Outline> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let stride = width + 1;
+        // Outgoing boundary edges of each pixel corner.
+        let mut edges = vec![0u8; stride * (height + 1)];
+        let mut previous: Vec<bool> = vec![false; width];
+        let mut current = vec![false; width];
+        for row in 0..=self.height {
+            let y = row as usize;
+            current.fill(false);
+            if row < self.height {
+                bits::for_each_run(&self.buffer, self.row_offset(row), width, |start, end| {
+                    current[start..end].fill(true);
+                    // Up the left side, down the right side.
+                    edges[(y + 1) * stride + start] |= NORTH;
+                    edges[y * stride + end] |= SOUTH;
+                });
+            }
+            for x in 0..width {
+                match (previous[x], current[x]) {
+                    (false, true) => edges[y * stride + x] |= EAST,
+                    (true, false) => edges[y * stride + x + 1] |= WEST,
+                    _ => {}
+                }
+            }
+            std::mem::swap(&mut previous, &mut current);
+        }
+
+        let step = |vertex: usize, direction: u8| match direction {
+            EAST => vertex + 1,
+            SOUTH => vertex + stride,
+            WEST => vertex - 1,
+            _ => vertex - stride,
+        };
+        let mut used = vec![0u8; edges.len()];
+        let mut outlines = Vec::new();
+        for start in 0..edges.len() {
+            while edges[start] & !used[start] != 0 {
+                let remaining = edges[start] & !used[start];
+                let first = remaining & remaining.wrapping_neg();
+                let mut points = Vec::new();
+                let (mut vertex, mut direction) = (start, first);
+                loop {
+                    used[vertex] |= direction;
+                    let next = step(vertex, direction);
+                    // At a corner where two outlines meet, turning left
+                    // keeps diagonal neighbors together.
+                    let options = edges[next];
+                    let next_direction = if options.is_power_of_two() {
+                        options
+                    } else {
+                        turn(direction, connectivity == Connectivity::Four)
+                    };
+                    if next_direction != direction {
+                        #[allow(clippy::cast_possible_truncation)]
+                        points.push(((next % stride) as u32, (next / stride) as u32));
+                    }
+                    if next == start && next_direction == first {
+                        break;
+                    }
+                    (vertex, direction) = (next, next_direction);
+                }
+                // Start at the first corner.
+                points.rotate_right(1);
+                outlines.push(Outline { points });
+            }
+        }
+        outlines
+    }
+}
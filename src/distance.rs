@@ -0,0 +1,245 @@
+#![allow(clippy::cast_precision_loss)]
+
+use crate::BinaryImage;
+
+impl BinaryImage {
+    /// The squared Euclidean distance from each pixel to the nearest set
+    /// pixel, in row-major order, or `u64::MAX` everywhere if no pixel is
+    /// set.
+    ///
+    /// Computed exactly in linear time by first finding the nearest set pixel
+    /// in each column, then taking the lower envelope of the resulting
+    /// parabolas along each row, as in Felzenszwalb and Huttenlocher,
+    /// "Distance Transforms of Sampled Functions", 2012.
+    #[must_use]
+    pub fn squared_distance_transform(&self) -> Vec<u64> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut distances = vec![u64::MAX; width * height];
+        // Vertical distance to the nearest set pixel in the column.
+        for x in 0..width {
+            let mut last = None;
+            for y in 0..height {
+                if self.buffer[y * width + x] {
+                    last = Some(y);
+                }
+                if let Some(last) = last {
+                    distances[y * width + x] = (y - last) as u64;
+                }
+            }
+            let mut next = None;
+            for y in (0..height).rev() {
+                if self.buffer[y * width + x] {
+                    next = Some(y);
+                }
+                if let Some(next) = next {
+                    let distance = &mut distances[y * width + x];
+                    *distance = (*distance).min((next - y) as u64);
+                }
+            }
+        }
+
+        let mut envelope = Envelope::default();
+        for row in distances.chunks_mut(width.max(1)) {
+            envelope.clear();
+            for (x, &distance) in row.iter().enumerate() {
+                if distance != u64::MAX {
+                    envelope.push(x.cast_signed() as i64, distance * distance);
+                }
+            }
+            if envelope.is_empty() {
+                continue;
+            }
+            envelope.lower(row);
+        }
+        distances
+    }
+}
+
+/// Distances between the set pixels of two images, see
+/// [`BinaryImage::shape_distances`].
+///
+/// A directed distance is 0 from an empty image, like
+/// [`BinaryImage::directed_hausdorff`], and infinite from a non-empty image
+/// to an empty one. So all distances are 0 if both images are empty, and all
+/// but the directed one from the empty image are infinite if only one is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeDistances {
+    /// Largest distance from a set pixel of the first image to the nearest
+    /// one of the second.
+    pub directed_hausdorff: f64,
+    /// Largest distance from a set pixel of the second image to the nearest
+    /// one of the first.
+    pub reverse_hausdorff: f64,
+    /// Larger of the two directed distances.
+    pub hausdorff: f64,
+    /// 95th percentile of the distances in both directions, which ignores a
+    /// few outliers.
+    pub hausdorff95: f64,
+    /// Mean of the mean distances in both directions.
+    pub chamfer: f64,
+}
+
+impl BinaryImage {
+    /// Measures how far the set pixels of the image are from those of
+    /// `other`, with one distance transform of each image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size.
+    #[must_use]
+    pub fn shape_distances(&self, other: &BinaryImage) -> ShapeDistances {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "Images differ in size"
+        );
+        let mut forward = distances(self, other);
+        let mut backward = distances(other, self);
+        if forward.is_empty() && backward.is_empty() {
+            return ShapeDistances {
+                directed_hausdorff: 0.0,
+                reverse_hausdorff: 0.0,
+                hausdorff: 0.0,
+                hausdorff95: 0.0,
+                chamfer: 0.0,
+            };
+        }
+        let max = |distances: &[f64]| distances.iter().copied().fold(0.0, f64::max);
+        let mean = |distances: &[f64]| {
+            if distances.is_empty() {
+                0.0
+            } else {
+                distances.iter().sum::<f64>() / distances.len() as f64
+            }
+        };
+        let (directed_hausdorff, reverse_hausdorff) = (max(&forward), max(&backward));
+        let chamfer = f64::midpoint(mean(&forward), mean(&backward));
+        forward.append(&mut backward);
+        ShapeDistances {
+            directed_hausdorff,
+            reverse_hausdorff,
+            hausdorff: directed_hausdorff.max(reverse_hausdorff),
+            hausdorff95: percentile(&mut forward, 95.0),
+            chamfer,
+        }
+    }
+
+    /// Largest distance from a set pixel of the image to the nearest one of
+    /// `other`, 0 if the image is empty and infinite if only `other` is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size.
+    #[must_use]
+    pub fn directed_hausdorff(&self, other: &BinaryImage) -> f64 {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "Images differ in size"
+        );
+        distances(self, other).into_iter().fold(0.0, f64::max)
+    }
+
+    /// Hausdorff distance between the set pixels of two images, see
+    /// [`ShapeDistances::hausdorff`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images differ in size.
+    #[must_use]
+    pub fn hausdorff(&self, other: &BinaryImage) -> f64 {
+        self.directed_hausdorff(other)
+            .max(other.directed_hausdorff(self))
+    }
+}
+
+/// The `p`th percentile of non-empty `values`, interpolating linearly between
+/// ranks like `numpy.percentile`.
+fn percentile(values: &mut [f64], p: f64) -> f64 {
+    values.sort_unstable_by(f64::total_cmp);
+    let rank = p / 100.0 * (values.len() - 1) as f64;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let low = rank.floor() as usize;
+    let high = (low + 1).min(values.len() - 1);
+    let (a, b) = (values[low], values[high]);
+    // Either all distances are infinite or none.
+    if b.is_infinite() {
+        b
+    } else {
+        a + (b - a) * (rank - low as f64)
+    }
+}
+
+/// The distance from each set pixel of `from` to the nearest set pixel of
+/// `to`, infinite if `to` is empty.
+pub(crate) fn distances(from: &BinaryImage, to: &BinaryImage) -> Vec<f64> {
+    let transform = to.squared_distance_transform();
+    from.buffer
+        .iter()
+        .zip(transform)
+        .filter(|&(set, _)| set)
+        .map(|(_, squared)| {
+            if squared == u64::MAX {
+                f64::INFINITY
+            } else {
+                (squared as f64).sqrt()
+            }
+        })
+        .collect()
+}
+
+/// The lower envelope of parabolas `(x - vertex)² + height`.
+#[derive(Default)]
+struct Envelope {
+    vertices: Vec<i64>,
+    heights: Vec<u64>,
+    /// Where each parabola starts to be the lowest, the first at -∞.
+    starts: Vec<f64>,
+}
+
+impl Envelope {
+    fn clear(&mut self) {
+        self.vertices.clear();
+        self.heights.clear();
+        self.starts.clear();
+    }
+
+    fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Adds a parabola with a vertex right of all others.
+    fn push(&mut self, vertex: i64, height: u64) {
+        while let (Some(&last), Some(&last_height), Some(&start)) = (
+            self.vertices.last(),
+            self.heights.last(),
+            self.starts.last(),
+        ) {
+            let intersection = ((height as f64 + (vertex * vertex) as f64)
+                - (last_height as f64 + (last * last) as f64))
+                / (2 * (vertex - last)) as f64;
+            if intersection > start {
+                self.vertices.push(vertex);
+                self.heights.push(height);
+                self.starts.push(intersection);
+                return;
+            }
+            self.vertices.pop();
+            self.heights.pop();
+            self.starts.pop();
+        }
+        self.vertices.push(vertex);
+        self.heights.push(height);
+        self.starts.push(f64::NEG_INFINITY);
+    }
+
+    /// Replaces each value of `row` with the envelope at its position.
+    fn lower(&self, row: &mut [u64]) {
+        let mut k = 0;
+        for (x, value) in (0..).zip(row.iter_mut()) {
+            while k + 1 < self.starts.len() && self.starts[k + 1] < x as f64 {
+                k += 1;
+            }
+            let offset = x - self.vertices[k];
+            *value = offset.unsigned_abs().pow(2) + self.heights[k];
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use bit_vec::BitVec;
+use image::math::Rect;
+
+use crate::{bits, BinaryImage, Bit};
+
+impl BinaryImage {
+    #[inline]
+    pub(crate) fn row_offset(&self, y: u32) -> usize {
+        y as usize * self.width as usize
+    }
+
+    /// Returns a copy of the rectangle at (`x`, `y`) with the given size.
+    ///
+    /// The rectangle is clamped to the image bounds, like [`image::imageops::crop_imm`].
+    #[must_use]
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> BinaryImage {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+
+        let mut out = BinaryImage::new(width, height);
+        for row in 0..height {
+            let dst = out.row_offset(row);
+            bits::copy(
+                &self.buffer,
+                self.row_offset(y + row) + x as usize,
+                &mut out.buffer,
+                dst,
+                width as usize,
+            );
+        }
+        out
+    }
+
+    /// Returns a copy of the image surrounded by borders of the given widths
+    /// filled with `fill`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the padded width or height does not fit in a `u32`.
+    #[must_use]
+    pub fn pad(&self, left: u32, top: u32, right: u32, bottom: u32, fill: Bit) -> BinaryImage {
+        let grow = |size: u32, before: u32, after: u32| {
+            size.checked_add(before)
+                .and_then(|size| size.checked_add(after))
+                .expect("Padded image too large")
+        };
+        let width = grow(self.width, left, right);
+        let height = grow(self.height, top, bottom);
+        let mut out = BinaryImage {
+            width,
+            height,
+            buffer: BitVec::from_elem(width as usize * height as usize, *fill),
+        };
+        for row in 0..self.height {
+            let dst = out.row_offset(top + row) + left as usize;
+            bits::copy(
+                &self.buffer,
+                self.row_offset(row),
+                &mut out.buffer,
+                dst,
+                self.width as usize,
+            );
+        }
+        out
+    }
+
+    /// Translates the content by (`dx`, `dy`) in place, filling uncovered
+    /// pixels with `fill`. Content moved past the edges is discarded.
+    pub fn shift(&mut self, dx: i32, dy: i32, fill: Bit) {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let len = width.saturating_sub(dx.unsigned_abs() as usize);
+        let (src_x, dst_x) = if dx >= 0 {
+            (0, width - len)
+        } else {
+            (width - len, 0)
+        };
+
+        let mut shift_row = |y: usize| {
+            let dst = y * width;
+            match y
+                .checked_add_signed(-(dy as isize))
+                .filter(|&src| src < height)
+            {
+                Some(src) if len > 0 => {
+                    bits::copy_within(&mut self.buffer, src * width + src_x, dst + dst_x, len);
+                    bits::fill(&mut self.buffer, dst, dst_x, *fill);
+                    bits::fill(
+                        &mut self.buffer,
+                        dst + dst_x + len,
+                        width - dst_x - len,
+                        *fill,
+                    );
+                }
+                _ => bits::fill(&mut self.buffer, dst, width, *fill),
+            }
+        };
+        // Rows must be visited away from the direction of travel so that every
+        // source row is read before it is overwritten.
+        if dy > 0 {
+            (0..height).rev().for_each(&mut shift_row);
+        } else {
+            (0..height).for_each(&mut shift_row);
+        }
+    }
+
+    /// Returns the smallest rectangle containing every set pixel, or `None`
+    /// if the image is empty.
+    #[must_use]
+    pub fn bounding_box(&self) -> Option<Rect> {
+        let width = self.width as usize;
+        let mut rows = (0..self.height).filter_map(|y| {
+            let offset = self.row_offset(y);
+            bits::first_one(&self.buffer, offset, width).map(|first| {
+                (
+                    y,
+                    first,
+                    bits::last_one(&self.buffer, offset, width).unwrap_or(first),
+                )
+            })
+        });
+        let (top, mut left, mut right) = rows.next()?;
+        let mut bottom = top;
+        for (y, first, last) in rows {
+            bottom = y;
+            left = left.min(first);
+            right = right.max(last);
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        Some(Rect {
+            x: left as u32,
+            y: top,
+            width: (right - left + 1) as u32,
+            height: bottom - top + 1,
+        })
+    }
+
+    /// Crops the image to the bounding box of its set pixels.
+    ///
+    /// Returns the bounding box together with the cropped image, or `None` if
+    /// no pixel is set.
+    #[must_use]
+    pub fn trim(&self) -> Option<(Rect, BinaryImage)> {
+        let rect = self.bounding_box()?;
+        Some((rect, self.crop(rect.x, rect.y, rect.width, rect.height)))
+    }
+}
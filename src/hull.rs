@@ -0,0 +1,166 @@
+use crate::{bits, BinaryImage, Components};
+
+/// The convex hull of `points` by Andrew's monotone chain, clockwise on
+/// screen with `y` pointing down and without collinear vertices.
+pub(crate) fn convex_hull(mut points: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+    points.sort_unstable();
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let cross = |o: (i64, i64), a: (i64, i64), b: (i64, i64)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    // The lower chain left to right, then the upper chain back, each point
+    // replacing those it makes turn the wrong way.
+    let mut hull: Vec<(i64, i64)> = Vec::with_capacity(points.len() + 1);
+    for &point in &points {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0 {
+            hull.pop();
+        }
+        hull.push(point);
+    }
+    let lower = hull.len();
+    for &point in points.iter().rev().skip(1) {
+        while hull.len() > lower && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0 {
+            hull.pop();
+        }
+        hull.push(point);
+    }
+    hull.pop();
+    hull
+}
+
+/// Twice the area of a polygon.
+pub(crate) fn twice_area(polygon: &[(i64, i64)]) -> i64 {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(&(x0, y0), &(x1, y1))| x0 * y1 - x1 * y0)
+        .sum::<i64>()
+        .abs()
+}
+
+impl BinaryImage {
+    /// The pixel corners that can lie on the convex hull of the set pixels:
+    /// those of the first and last set pixel of each row.
+    pub(crate) fn hull_candidates(&self) -> Vec<(i64, i64)> {
+        let mut points = Vec::new();
+        for y in 0..self.height {
+            let (offset, len) = (self.row_offset(y), self.width as usize);
+            let Some(first) = bits::first_one(&self.buffer, offset, len) else {
+                continue;
+            };
+            let last = bits::last_one(&self.buffer, offset, len).unwrap_or(first);
+            let (left, right) = (first.cast_signed() as i64, last.cast_signed() as i64 + 1);
+            let (top, bottom) = (i64::from(y), i64::from(y) + 1);
+            points.extend([(left, top), (left, bottom), (right, top), (right, bottom)]);
+        }
+        points
+    }
+
+    /// The convex hull of the set pixels as a polygon through pixel corners,
+    /// clockwise on screen like an outer [`Outline`](crate::Outline) and
+    /// without collinear vertices, or empty if no pixel is set.
+    ///
+    /// Only the first and last set pixel of each row can lie on the hull, so
+    /// the monotone chain runs over at most four corners per row.
+    #[must_use]
+    pub fn convex_hull(&self) -> Vec<(u32, u32)> {
+        to_corners(&convex_hull(self.hull_candidates()))
+    }
+
+    /// The pixels whose centers lie inside or on the
+    /// [`convex_hull`](BinaryImage::convex_hull), which include all set
+    /// pixels, like `convex_hull_image` in scikit-image.
+    #[must_use]
+    pub fn convex_hull_image(&self) -> BinaryImage {
+        let mut image = BinaryImage::new(self.width, self.height);
+        let hull = convex_hull(self.hull_candidates());
+        for y in 0..self.height {
+            if let Some((start, end)) = span(&hull, i64::from(y)) {
+                let start = start.clamp(0, i64::from(self.width));
+                let end = end.clamp(start, i64::from(self.width));
+                let offset = image.row_offset(y);
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                bits::fill(
+                    &mut image.buffer,
+                    offset + start as usize,
+                    (end - start) as usize,
+                    true,
+                );
+            }
+        }
+        image
+    }
+}
+
+impl Components {
+    /// The [convex hull](BinaryImage::convex_hull) of each component, indexed
+    /// by label minus one.
+    #[must_use]
+    pub fn convex_hulls(&self) -> Vec<Vec<(u32, u32)>> {
+        let mut candidates = vec![Vec::new(); self.count() as usize];
+        for (y, row) in (0..self.height()).zip(self.labels().chunks(self.width().max(1) as usize)) {
+            let (top, bottom) = (i64::from(y), i64::from(y) + 1);
+            let mut start = 0;
+            for (x, &label) in (0..self.width()).zip(row) {
+                // Only the ends of each run can lie on the hull.
+                if row.get(x as usize + 1) != Some(&label) {
+                    if label > 0 {
+                        let (left, right) = (i64::from(start), i64::from(x) + 1);
+                        candidates[label as usize - 1].extend([
+                            (left, top),
+                            (left, bottom),
+                            (right, top),
+                            (right, bottom),
+                        ]);
+                    }
+                    start = x + 1;
+                }
+            }
+        }
+        candidates
+            .into_iter()
+            .map(|points| to_corners(&convex_hull(points)))
+            .collect()
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn to_corners(hull: &[(i64, i64)]) -> Vec<(u32, u32)> {
+    hull.iter().map(|&(x, y)| (x as u32, y as u32)).collect()
+}
+
+/// The half-open range of pixels in row `y` whose centers lie inside or on a
+/// clockwise convex polygon, if any.
+fn span(hull: &[(i64, i64)], y: i64) -> Option<(i64, i64)> {
+    // In doubled coordinates, pixel centers are odd.
+    let center = 2 * y + 1;
+    let (mut start, mut end) = (i64::MIN, i64::MAX);
+    let mut crossed = false;
+    for (&(x0, y0), &(x1, y1)) in hull.iter().zip(hull.iter().cycle().skip(1)) {
+        let (x0, y0, x1, y1) = (2 * x0, 2 * y0, 2 * x1, 2 * y1);
+        if y0 == y1 || center < y0.min(y1) || center > y0.max(y1) {
+            continue;
+        }
+        crossed = true;
+        // Clockwise on screen, edges going down bound the row on the right.
+        // The edge crosses the row at `numerator / height + 1`, so the pixel
+        // at `x` is within it if `2x + 1` is.
+        let descends = y0 < y1;
+        let (x0, y0, x1, y1) = if descends {
+            (x0, y0, x1, y1)
+        } else {
+            (x1, y1, x0, y0)
+        };
+        let height = y1 - y0;
+        let numerator = x0 * height + (x1 - x0) * (center - y0) - height;
+        if descends {
+            end = end.min(numerator.div_euclid(2 * height) + 1);
+        } else {
+            start = start.max(-(-numerator).div_euclid(2 * height));
+        }
+    }
+    (crossed && start < end).then_some((start, end))
+}
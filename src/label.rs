@@ -0,0 +1,182 @@
+use image::math::Rect;
+
+use crate::{bits, BinaryImage, Connectivity};
+
+/// The connected components of the set pixels of an image.
+///
+/// Components are numbered from 1 in the order their first pixel appears in
+/// row-major order; clear pixels have label 0.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Components {
+    width: u32,
+    height: u32,
+    count: u32,
+    labels: Vec<u32>,
+}
+
+impl Components {
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Number of components.
+    #[inline]
+    #[must_use]
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// The label of the pixel at (`x`, `y`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pixel is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn label(&self, x: u32, y: u32) -> u32 {
+        assert!(x < self.width && y < self.height, "Pixel out of bounds");
+        self.labels[y as usize * self.width as usize + x as usize]
+    }
+
+    /// The labels of all pixels in row-major order.
+    #[must_use]
+    pub fn labels(&self) -> &[u32] {
+        &self.labels
+    }
+
+    /// An image with only the pixels of component `label` set.
+    #[must_use]
+    pub fn mask(&self, label: u32) -> BinaryImage {
+        let mut mask = BinaryImage::new(self.width, self.height);
+        for (i, _) in self.labels.iter().enumerate().filter(|&(_, &l)| l == label) {
+            mask.buffer.set(i, true);
+        }
+        mask
+    }
+
+    /// Number of pixels of each component, indexed by label minus one.
+    #[must_use]
+    pub fn areas(&self) -> Vec<u64> {
+        let mut areas = vec![0; self.count as usize];
+        for &label in self.labels.iter().filter(|&&label| label > 0) {
+            areas[label as usize - 1] += 1;
+        }
+        areas
+    }
+
+    /// Bounding box of each component, indexed by label minus one.
+    #[must_use]
+    pub fn bounding_boxes(&self) -> Vec<Rect> {
+        // Inclusive bounds, left, top, right, bottom.
+        let mut bounds = vec![[u32::MAX, u32::MAX, 0, 0]; self.count as usize];
+        for (y, row) in (0..self.height).zip(self.labels.chunks(self.width.max(1) as usize)) {
+            for (x, &label) in (0..self.width).zip(row) {
+                if label > 0 {
+                    let bound = &mut bounds[label as usize - 1];
+                    *bound = [
+                        bound[0].min(x),
+                        bound[1].min(y),
+                        bound[2].max(x),
+                        bound[3].max(y),
+                    ];
+                }
+            }
+        }
+        bounds
+            .into_iter()
+            .map(|[left, top, right, bottom]| Rect {
+                x: left,
+                y: top,
+                width: right - left + 1,
+                height: bottom - top + 1,
+            })
+            .collect()
+    }
+}
+
+impl BinaryImage {
+    /// Labels the connected components of the set pixels.
+    ///
+    /// Runs of set pixels are merged with the overlapping runs of the row
+    /// above through a union-find, found in one sweep over both rows, so the
+    /// cost grows linearly with the number of runs rather than the number of
+    /// pixels, apart from writing the labels.
+    #[must_use]
+    pub fn components(&self, connectivity: Connectivity) -> Components {
+        // Diagonal neighbors extend a run by one pixel on each side.
+        let reach = usize::from(connectivity == Connectivity::Eight);
+        let mut runs: Vec<(u32, usize, usize)> = Vec::new();
+        let mut parents: Vec<usize> = Vec::new();
+        let mut above = 0..0;
+        for y in 0..self.height {
+            let first = runs.len();
+            // Both rows are sorted, so one sweep finds all overlaps, skipping
+            // the runs above that end before the current run can reach them.
+            let mut next = above.start;
+            bits::for_each_run(
+                &self.buffer,
+                self.row_offset(y),
+                self.width as usize,
+                |start, end| {
+                    let node = runs.len();
+                    parents.push(node);
+                    while next < above.end && runs[next].2 + reach <= start {
+                        next += 1;
+                    }
+                    let mut other = next;
+                    while other < above.end && runs[other].1 < end + reach {
+                        union(&mut parents, node, other);
+                        other += 1;
+                    }
+                    runs.push((y, start, end));
+                },
+            );
+            above = first..runs.len();
+        }
+
+        // Roots are the first run of their component, so labels follow the
+        // order of first appearance.
+        let mut labels = vec![0; self.width as usize * self.height as usize];
+        let mut root_labels = vec![0; runs.len()];
+        let mut count = 0;
+        for (node, &(y, start, end)) in runs.iter().enumerate() {
+            let root = find(&mut parents, node);
+            if root == node {
+                count += 1;
+                root_labels[node] = count;
+            }
+            let offset = self.row_offset(y);
+            labels[offset + start..offset + end].fill(root_labels[root]);
+        }
+        Components {
+            width: self.width,
+            height: self.height,
+            count,
+            labels,
+        }
+    }
+}
+
+/// Merges the sets of `a` and `b`, keeping the smaller root.
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parents, a), find(parents, b));
+    let (low, high) = (a.min(b), a.max(b));
+    parents[high] = low;
+}
+
+/// The root of the set of `node`, halving the path on the way.
+fn find(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
@@ -3,14 +3,51 @@
 use bit_vec::BitVec;
 use image::{GenericImage, GenericImageView, Pixel};
 
+#[doc(hidden)]
+pub use ascii::validate_rows as __validate_ascii_rows;
+pub use ascii::{AsciiError, AsciiFormat};
+#[doc(hidden)]
+pub use compare::assert_failed as __assert_binary_eq_failed;
+pub use compare::{diff, Diff};
+pub use contour::{Connectivity, Outline};
+pub use distance::ShapeDistances;
+pub use label::Components;
+pub use metrics::{evaluate, Metrics};
+pub use moments::Moments;
+pub use packed::{BitOrder, PackedLayout};
 pub use pixel::Bit;
-pub use view::BinaryView;
-
+pub use region::{Perimeter, RegionOptions, RegionProps};
+pub use render::{AnsiColors, Glyphs, Rendered, TextOptions};
+pub use rle::RleImage;
+pub use trace::{Segment, TraceOptions, TracedPath};
+pub use view::{
+    AndView, BinarySubView, BinarySubViewMut, BinaryView, BitImageView, NotView, OrView,
+    TransformedView, XorView,
+};
+
+mod ascii;
 #[cfg(feature = "bevy")]
 pub mod bevy;
+mod bits;
+pub mod codecs;
+mod compare;
+mod contour;
+mod distance;
+mod geometry;
+mod hull;
+mod label;
+mod metrics;
+mod moments;
+mod packed;
 mod pixel;
+mod region;
+mod render;
+mod rle;
+#[cfg(feature = "serde")]
+mod serialization;
 #[cfg(test)]
 mod tests;
+mod trace;
 mod view;
 
 #[derive(Debug, Clone, Default)]
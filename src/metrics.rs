@@ -0,0 +1,167 @@
+#![allow(clippy::cast_precision_loss)]
+
+use crate::distance::distances;
+use crate::{bits, BinaryImage};
+
+/// How well a predicted mask matches a ground truth, see [`evaluate`].
+///
+/// Ratios over no pixels, such as the precision of an empty prediction, are 1
+/// when the other side is empty too and 0 otherwise, so two empty masks match
+/// perfectly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// Pixels set in both masks.
+    pub true_positives: u64,
+    /// Pixels set only in the prediction.
+    pub false_positives: u64,
+    /// Pixels set only in the ground truth.
+    pub false_negatives: u64,
+    /// Pixels clear in both masks.
+    pub true_negatives: u64,
+    /// Intersection over union, or Jaccard index.
+    pub iou: f64,
+    /// Dice coefficient, or pixel F1 score.
+    pub dice: f64,
+    pub precision: f64,
+    pub recall: f64,
+    pub accuracy: f64,
+    /// Fraction of the predicted boundary within the tolerance of the true
+    /// boundary.
+    pub boundary_precision: f64,
+    /// Fraction of the true boundary within the tolerance of the predicted
+    /// boundary.
+    pub boundary_recall: f64,
+    /// Harmonic mean of the boundary precision and recall.
+    pub boundary_f1: f64,
+    /// Average symmetric surface distance: the mean distance from each
+    /// boundary pixel of either mask to the nearest boundary pixel of the
+    /// other, infinite if only one mask is empty.
+    pub assd: f64,
+}
+
+/// Compares a `predicted` mask with the `truth`.
+///
+/// Pixel counts come from popcounts over the packed buffers. Boundaries are
+/// the set pixels with a clear or missing neighbor above, below, left or
+/// right, and boundary pixels match if they are at most `tolerance` pixels
+/// apart.
+///
+/// # Panics
+///
+/// Panics if the masks differ in size.
+#[must_use]
+pub fn evaluate(predicted: &BinaryImage, truth: &BinaryImage, tolerance: f64) -> Metrics {
+    assert!(
+        predicted.width == truth.width && predicted.height == truth.height,
+        "Masks differ in size"
+    );
+    let len = predicted.width as usize * predicted.height as usize;
+    let (mut true_positives, mut false_positives, mut false_negatives) = (0, 0, 0);
+    for (a, b) in bits::blocks(&predicted.buffer, len).zip(bits::blocks(&truth.buffer, len)) {
+        true_positives += u64::from((a & b).count_ones());
+        false_positives += u64::from((a & !b).count_ones());
+        false_negatives += u64::from((!a & b).count_ones());
+    }
+    let true_negatives = len as u64 - true_positives - false_positives - false_negatives;
+
+    let predicted_boundary = predicted.boundary();
+    let true_boundary = truth.boundary();
+    let to_truth = distances(&predicted_boundary, &true_boundary);
+    let to_predicted = distances(&true_boundary, &predicted_boundary);
+    let within = |distances: &[f64]| distances.iter().filter(|&&d| d <= tolerance).count() as u64;
+    let boundary_precision = ratio(
+        within(&to_truth),
+        to_truth.len() as u64,
+        to_predicted.is_empty(),
+    );
+    let boundary_recall = ratio(
+        within(&to_predicted),
+        to_predicted.len() as u64,
+        to_truth.is_empty(),
+    );
+    let count = to_truth.len() + to_predicted.len();
+    let assd = if count == 0 {
+        0.0
+    } else {
+        (to_truth.iter().sum::<f64>() + to_predicted.iter().sum::<f64>()) / count as f64
+    };
+
+    Metrics {
+        true_positives,
+        false_positives,
+        false_negatives,
+        true_negatives,
+        iou: ratio(
+            true_positives,
+            true_positives + false_positives + false_negatives,
+            true,
+        ),
+        dice: ratio(
+            2 * true_positives,
+            2 * true_positives + false_positives + false_negatives,
+            true,
+        ),
+        precision: ratio(
+            true_positives,
+            true_positives + false_positives,
+            false_negatives == 0,
+        ),
+        recall: ratio(
+            true_positives,
+            true_positives + false_negatives,
+            false_positives == 0,
+        ),
+        accuracy: ratio(true_positives + true_negatives, len as u64, true),
+        boundary_precision,
+        boundary_recall,
+        boundary_f1: if boundary_precision + boundary_recall == 0.0 {
+            0.0
+        } else {
+            2.0 * boundary_precision * boundary_recall / (boundary_precision + boundary_recall)
+        },
+        assd,
+    }
+}
+
+impl BinaryImage {
+    /// Compares the image as a predicted mask with the `truth`, see
+    /// [`evaluate`].
+    #[must_use]
+    pub fn evaluate(&self, truth: &BinaryImage, tolerance: f64) -> Metrics {
+        evaluate(self, truth, tolerance)
+    }
+
+    /// The set pixels with a clear or missing 4-neighbor.
+    pub(crate) fn boundary(&self) -> BinaryImage {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut boundary = BinaryImage::new(self.width, self.height);
+        let is_set = |x: usize, y: usize| self.buffer[y * width + x];
+        for y in 0..height {
+            for x in 0..width {
+                if is_set(x, y)
+                    && (x == 0
+                        || y == 0
+                        || x + 1 == width
+                        || y + 1 == height
+                        || !is_set(x - 1, y)
+                        || !is_set(x + 1, y)
+                        || !is_set(x, y - 1)
+                        || !is_set(x, y + 1))
+                {
+                    boundary.buffer.set(y * width + x, true);
+                }
+            }
+        }
+        boundary
+    }
+}
+
+/// `numerator / denominator`, or whether the other side is empty if the
+/// denominator is 0.
+fn ratio(numerator: u64, denominator: u64, other_empty: bool) -> f64 {
+    if denominator == 0 {
+        f64::from(u8::from(other_empty))
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
@@ -0,0 +1,230 @@
+#![allow(
+    clippy::cast_precision_loss,
+    clippy::many_single_char_names,
+    clippy::needless_range_loop
+)]
+
+use crate::{bits, BinaryImage, Components};
+
+/// Moments of a set of pixels up to third order, with pixel `(x, y)` at its
+/// center `(x, y)` and `y` pointing down.
+///
+/// Each array is indexed `[p][q]` for the moment of order `p` in `x` and `q`
+/// in `y`; entries with `p + q > 3` are 0.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Moments {
+    /// Spatial moments, `m[p][q]` = Σ x^p y^q.
+    pub spatial: [[f64; 4]; 4],
+    /// Central moments about the centroid, `mu[p][q]` = Σ (x - x̄)^p (y - ȳ)^q.
+    pub central: [[f64; 4]; 4],
+    /// Scale-invariant central moments, `nu[p][q]` = `mu[p][q]` /
+    /// `mu[0][0]`^(1 + (p + q) / 2), all 0 for no pixels.
+    pub normalized: [[f64; 4]; 4],
+}
+
+impl Moments {
+    /// Computes the moments of pixel runs `(y, start, end)`, with half-open
+    /// bounds.
+    fn from_runs(runs: &[(u32, u32, u32)]) -> Self {
+        // Sum exactly in integers about a pixel near the centroid, so the
+        // higher moments do not lose precision to cancellation.
+        let (mut count, mut sum_x, mut sum_y) = (0i128, 0i128, 0i128);
+        for &(y, start, end) in runs {
+            let (a, b) = (i128::from(start), i128::from(end));
+            count += b - a;
+            sum_x += power_sum(1, a, b);
+            sum_y += (b - a) * i128::from(y);
+        }
+        if count == 0 {
+            return Self::default();
+        }
+        let origin = (sum_x / count, sum_y / count);
+        let mut sums = [[0i128; 4]; 4];
+        for &(y, start, end) in runs {
+            let (a, b) = (i128::from(start) - origin.0, i128::from(end) - origin.0);
+            let y = i128::from(y) - origin.1;
+            let y_powers = [1, y, y * y, y * y * y];
+            for p in 0..4 {
+                let x_sum = power_sum(p, a, b);
+                for q in 0..4 - p {
+                    sums[p][q] += x_sum * y_powers[q];
+                }
+            }
+        }
+
+        let sums = sums.map(|row| row.map(|sum| sum as f64));
+        let (origin_x, origin_y) = (origin.0 as f64, origin.1 as f64);
+        let spatial = shift(&sums, -origin_x, -origin_y);
+        let centroid = (spatial[1][0] / spatial[0][0], spatial[0][1] / spatial[0][0]);
+        let central = shift(&sums, centroid.0 - origin_x, centroid.1 - origin_y);
+        let mut normalized = [[0.0; 4]; 4];
+        for p in 0..4 {
+            for q in 0..4 - p {
+                normalized[p][q] = central[p][q] / central[0][0].powf(1.0 + (p + q) as f64 / 2.0);
+            }
+        }
+        Self {
+            spatial,
+            central,
+            normalized,
+        }
+    }
+
+    /// Number of pixels.
+    #[must_use]
+    pub fn area(&self) -> f64 {
+        self.spatial[0][0]
+    }
+
+    /// Center of mass, if there are any pixels.
+    #[must_use]
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        let m = &self.spatial;
+        (m[0][0] > 0.0).then(|| (m[1][0] / m[0][0], m[0][1] / m[0][0]))
+    }
+
+    /// Hu's seven moments, invariant to translation, scale and rotation; the
+    /// seventh changes sign under reflection.
+    #[must_use]
+    pub fn hu(&self) -> [f64; 7] {
+        let n = &self.normalized;
+        let (n20, n02, n11) = (n[2][0], n[0][2], n[1][1]);
+        let (n30, n03, n21, n12) = (n[3][0], n[0][3], n[2][1], n[1][2]);
+        let (a, b) = (n30 + n12, n21 + n03);
+        [
+            n20 + n02,
+            (n20 - n02).powi(2) + 4.0 * n11 * n11,
+            (n30 - 3.0 * n12).powi(2) + (3.0 * n21 - n03).powi(2),
+            a * a + b * b,
+            (n30 - 3.0 * n12) * a * (a * a - 3.0 * b * b)
+                + (3.0 * n21 - n03) * b * (3.0 * a * a - b * b),
+            (n20 - n02) * (a * a - b * b) + 4.0 * n11 * a * b,
+            (3.0 * n21 - n03) * a * (a * a - 3.0 * b * b)
+                - (n30 - 3.0 * n12) * b * (3.0 * a * a - b * b),
+        ]
+    }
+
+    /// Variances along the principal axes, larger first.
+    fn principal_variances(&self) -> (f64, f64) {
+        let mu = &self.central;
+        if mu[0][0] == 0.0 {
+            return (0.0, 0.0);
+        }
+        let (a, b, c) = (
+            mu[2][0] / mu[0][0],
+            mu[1][1] / mu[0][0],
+            mu[0][2] / mu[0][0],
+        );
+        let mean = f64::midpoint(a, c);
+        let spread = ((a - c) / 2.0).hypot(b);
+        (mean + spread, (mean - spread).max(0.0))
+    }
+
+    /// Angle of the major axis from the `x` axis in radians, in `[-π/2,
+    /// π/2]`, positive toward `y`, so clockwise on screen.
+    #[must_use]
+    pub fn orientation(&self) -> f64 {
+        let mu = &self.central;
+        0.5 * (2.0 * mu[1][1]).atan2(mu[2][0] - mu[0][2])
+    }
+
+    /// Eccentricity of the ellipse with the same second moments, 0 for a
+    /// circle and approaching 1 for a line.
+    #[must_use]
+    pub fn eccentricity(&self) -> f64 {
+        let (major, minor) = self.principal_variances();
+        if major == 0.0 {
+            0.0
+        } else {
+            (1.0 - minor / major).sqrt()
+        }
+    }
+
+    /// Lengths of the major and minor axes of the ellipse with the same
+    /// second moments.
+    #[must_use]
+    pub fn ellipse_axes(&self) -> (f64, f64) {
+        let (major, minor) = self.principal_variances();
+        (4.0 * major.sqrt(), 4.0 * minor.sqrt())
+    }
+}
+
+/// Σ u^p for `u` in `a..b`, using the Faulhaber polynomials, which hold for
+/// negative bounds too.
+fn power_sum(p: usize, a: i128, b: i128) -> i128 {
+    let prefix = |n: i128| match p {
+        0 => n,
+        1 => n * (n - 1) / 2,
+        2 => (n - 1) * n * (2 * n - 1) / 6,
+        _ => (n * (n - 1) / 2).pow(2),
+    };
+    prefix(b) - prefix(a)
+}
+
+/// Moments about `(dx, dy)` from moments about the origin.
+fn shift(sums: &[[f64; 4]; 4], dx: f64, dy: f64) -> [[f64; 4]; 4] {
+    const BINOMIAL: [[f64; 4]; 4] = [
+        [1.0, 0.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0, 0.0],
+        [1.0, 2.0, 1.0, 0.0],
+        [1.0, 3.0, 3.0, 1.0],
+    ];
+    let powers = |d: f64| [1.0, -d, d * d, -d * d * d];
+    let (x_powers, y_powers) = (powers(dx), powers(dy));
+    let mut shifted = [[0.0; 4]; 4];
+    for p in 0..4 {
+        for q in 0..4 - p {
+            for i in 0..=p {
+                for j in 0..=q {
+                    shifted[p][q] += BINOMIAL[p][i]
+                        * BINOMIAL[q][j]
+                        * x_powers[p - i]
+                        * y_powers[q - j]
+                        * sums[i][j];
+                }
+            }
+        }
+    }
+    shifted
+}
+
+impl BinaryImage {
+    /// Moments of the set pixels.
+    #[must_use]
+    pub fn moments(&self) -> Moments {
+        let mut runs = Vec::new();
+        for y in 0..self.height {
+            bits::for_each_run(
+                &self.buffer,
+                self.row_offset(y),
+                self.width as usize,
+                |start, end| {
+                    #[allow(clippy::cast_possible_truncation)]
+                    runs.push((y, start as u32, end as u32));
+                },
+            );
+        }
+        Moments::from_runs(&runs)
+    }
+}
+
+impl Components {
+    /// Moments of each component, indexed by label minus one.
+    #[must_use]
+    pub fn moments(&self) -> Vec<Moments> {
+        let mut runs = vec![Vec::new(); self.count() as usize];
+        for (y, row) in (0..self.height()).zip(self.labels().chunks(self.width().max(1) as usize)) {
+            let mut start = 0;
+            for (x, &label) in (0..self.width()).zip(row) {
+                // A run ends where the next pixel has another label.
+                if row.get(x as usize + 1) != Some(&label) {
+                    if label > 0 {
+                        runs[label as usize - 1].push((y, start, x + 1));
+                    }
+                    start = x + 1;
+                }
+            }
+        }
+        runs.iter().map(|runs| Moments::from_runs(runs)).collect()
+    }
+}
@@ -0,0 +1,146 @@
+use crate::codecs::DecodingError;
+use crate::BinaryImage;
+
+/// Which bit of a byte holds the leftmost of its eight pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BitOrder {
+    /// The most significant bit, as in PBM, BMP, PNG and most framebuffers.
+    #[default]
+    MsbFirst,
+    /// The least significant bit, as in XBM.
+    LsbFirst,
+}
+
+/// How a buffer packs one pixel per bit, row by row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedLayout {
+    pub bit_order: BitOrder,
+    /// Rows start at multiples of this many bytes, 1 for tightly packed rows.
+    pub row_alignment: usize,
+    /// Bytes from the start of one row to the next, overriding
+    /// `row_alignment`. Must be at least the packed length of a row.
+    pub row_stride: Option<usize>,
+    /// Store set pixels as 0 bits.
+    pub invert: bool,
+}
+
+impl Default for PackedLayout {
+    fn default() -> Self {
+        Self {
+            bit_order: BitOrder::MsbFirst,
+            row_alignment: 1,
+            row_stride: None,
+            invert: false,
+        }
+    }
+}
+
+impl PackedLayout {
+    /// Bytes from the start of one row to the next for rows of `width`
+    /// pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row_stride` is too small for a row or `row_alignment` is 0.
+    #[must_use]
+    pub fn stride(&self, width: u32) -> usize {
+        let len = (width as usize).div_ceil(8);
+        match self.row_stride {
+            Some(stride) => {
+                assert!(stride >= len, "Row stride {stride} shorter than a row");
+                stride
+            }
+            None => len.next_multiple_of(self.row_alignment),
+        }
+    }
+
+    /// Bytes needed for an image of `width` by `height` pixels, without
+    /// padding after the last row.
+    #[must_use]
+    pub fn min_len(&self, width: u32, height: u32) -> usize {
+        match height {
+            0 => 0,
+            _ => self.stride(width) * (height as usize - 1) + (width as usize).div_ceil(8),
+        }
+    }
+}
+
+impl BinaryImage {
+    /// Reads an image from a buffer packing one pixel per bit.
+    ///
+    /// Padding bits and bytes are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodingError::UnexpectedEof`] if `bytes` is shorter than
+    /// [`PackedLayout::min_len`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layout is invalid, see [`PackedLayout::stride`].
+    pub fn from_packed(
+        width: u32,
+        height: u32,
+        bytes: &[u8],
+        layout: &PackedLayout,
+    ) -> Result<Self, DecodingError> {
+        if bytes.len() < layout.min_len(width, height) {
+            return Err(DecodingError::UnexpectedEof);
+        }
+        let mut image = BinaryImage::new(width, height);
+        let (stride, len) = (layout.stride(width), image.packed_row_len());
+        if len == 0 {
+            return Ok(image);
+        }
+        for (y, row) in (0..height).zip(bytes.chunks(stride)) {
+            image.unpack_row(
+                y,
+                &row[..len],
+                layout.bit_order == BitOrder::MsbFirst,
+                layout.invert,
+            );
+        }
+        Ok(image)
+    }
+
+    /// Packs the image into a new buffer of `stride * height` bytes, with all
+    /// padding zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layout is invalid, see [`PackedLayout::stride`].
+    #[must_use]
+    pub fn to_packed(&self, layout: &PackedLayout) -> Vec<u8> {
+        let mut bytes = vec![0; layout.stride(self.width) * self.height as usize];
+        self.write_packed(&mut bytes, layout);
+        bytes
+    }
+
+    /// Packs the image into an existing buffer such as a framebuffer.
+    ///
+    /// Padding bits at the end of each row are zeroed; padding bytes between
+    /// rows are left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layout is invalid or `bytes` is shorter than
+    /// [`PackedLayout::min_len`].
+    pub fn write_packed(&self, bytes: &mut [u8], layout: &PackedLayout) {
+        assert!(
+            bytes.len() >= layout.min_len(self.width, self.height),
+            "Buffer too small"
+        );
+        let (stride, len) = (layout.stride(self.width), self.packed_row_len());
+        if len == 0 {
+            return;
+        }
+        for (y, row) in (0..self.height).zip(bytes.chunks_mut(stride)) {
+            self.pack_row(
+                y,
+                &mut row[..len],
+                layout.bit_order == BitOrder::MsbFirst,
+                layout.invert,
+            );
+        }
+    }
+}
@@ -36,6 +36,11 @@ use num_traits::{One, Zero};
     Into,
     From,
 )]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Bit(pub bool);
 
 impl std::ops::AddAssign for Bit {
@@ -0,0 +1,250 @@
+#![allow(clippy::cast_precision_loss)]
+
+use std::f64::consts::{FRAC_PI_4, PI, SQRT_2};
+
+use image::math::Rect;
+
+use crate::hull::{convex_hull, twice_area};
+use crate::{BinaryImage, Connectivity, Moments};
+
+/// How [`RegionProps::perimeter`] is estimated.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Perimeter {
+    /// Cauchy-Crofton formula over lines in four directions, counting where
+    /// each line enters the region. Includes the boundaries of holes.
+    #[default]
+    Crofton,
+    /// Length of the 8-connected chain through the centers of the outer
+    /// boundary pixels, 1 per straight and √2 per diagonal step, so 0 for a
+    /// single pixel.
+    ChainCode,
+}
+
+/// Options for [`BinaryImage::region_props`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegionOptions {
+    /// Which pixels form a region, also used for its Euler number.
+    pub connectivity: Connectivity,
+    pub perimeter: Perimeter,
+}
+
+/// Shape measurements of a connected component, see
+/// [`BinaryImage::region_props`].
+///
+/// Lengths and areas treat pixels as unit squares, so the convex hull and
+/// Feret diameters run through pixel corners.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegionProps {
+    /// Label of the component, as in [`Components`](crate::Components).
+    pub label: u32,
+    /// Number of pixels.
+    pub area: u64,
+    pub bounding_box: Rect,
+    /// Moments in image coordinates.
+    pub moments: Moments,
+    pub perimeter: f64,
+    /// `4π · area / perimeter²`, about 1 for a disk and 0 without a
+    /// perimeter.
+    pub circularity: f64,
+    /// Area of the convex hull.
+    pub convex_area: f64,
+    /// Area over convex area.
+    pub solidity: f64,
+    /// Area over bounding box area.
+    pub extent: f64,
+    /// Largest distance between two points of the region.
+    pub feret_max: f64,
+    /// Smallest width of the region across any direction.
+    pub feret_min: f64,
+    /// Number of components minus number of holes.
+    pub euler_number: i64,
+}
+
+impl BinaryImage {
+    /// Measures each connected component, in label order.
+    #[must_use]
+    pub fn region_props(&self, options: &RegionOptions) -> Vec<RegionProps> {
+        let components = self.components(options.connectivity);
+        let moments = components.moments();
+        let labels = components.labels();
+        let width = self.width as usize;
+        components
+            .bounding_boxes()
+            .into_iter()
+            .zip(components.areas())
+            .zip(moments)
+            .zip(1..)
+            .map(|(((bounding_box, area), moments), label)| {
+                let mut mask = BinaryImage::new(bounding_box.width, bounding_box.height);
+                for y in 0..bounding_box.height as usize {
+                    let start = (bounding_box.y as usize + y) * width + bounding_box.x as usize;
+                    let row = &labels[start..start + bounding_box.width as usize];
+                    for (x, _) in row.iter().enumerate().filter(|&(_, &l)| l == label) {
+                        mask.buffer.set(y * bounding_box.width as usize + x, true);
+                    }
+                }
+
+                let perimeter = match options.perimeter {
+                    Perimeter::Crofton => mask.crofton_perimeter(),
+                    Perimeter::ChainCode => mask.chain_code_perimeter(),
+                };
+                let hull = convex_hull(mask.hull_candidates());
+                let convex_area = twice_area(&hull) as f64 / 2.0;
+                let (feret_max, feret_min) = feret_diameters(&hull);
+                RegionProps {
+                    label,
+                    area,
+                    bounding_box,
+                    moments,
+                    perimeter,
+                    circularity: if perimeter > 0.0 {
+                        4.0 * PI * area as f64 / (perimeter * perimeter)
+                    } else {
+                        0.0
+                    },
+                    convex_area,
+                    solidity: area as f64 / convex_area,
+                    extent: area as f64
+                        / (u64::from(bounding_box.width) * u64::from(bounding_box.height)) as f64,
+                    feret_max,
+                    feret_min,
+                    euler_number: mask.euler_number(options.connectivity),
+                }
+            })
+            .collect()
+    }
+
+    /// Number of components minus number of holes, with components and holes
+    /// taking the opposite connectivity.
+    ///
+    /// Counted from the 2×2 neighborhoods with one, three or two diagonal set
+    /// pixels, as in Gray, "Local Properties of Binary Images in Two
+    /// Dimensions", 1971.
+    #[must_use]
+    pub fn euler_number(&self, connectivity: Connectivity) -> i64 {
+        let (mut ones, mut threes, mut diagonals) = (0i64, 0i64, 0i64);
+        for y in 0..=i64::from(self.height) {
+            for x in 0..=i64::from(self.width) {
+                let quad = [
+                    self.is_set(x - 1, y - 1),
+                    self.is_set(x, y - 1),
+                    self.is_set(x - 1, y),
+                    self.is_set(x, y),
+                ];
+                match quad.iter().filter(|&&set| set).count() {
+                    1 => ones += 1,
+                    2 if quad[0] == quad[3] => diagonals += 1,
+                    3 => threes += 1,
+                    _ => {}
+                }
+            }
+        }
+        match connectivity {
+            Connectivity::Four => (ones - threes + 2 * diagonals) / 4,
+            Connectivity::Eight => (ones - threes - 2 * diagonals) / 4,
+        }
+    }
+
+    /// Whether the pixel at (`x`, `y`) is set, false outside the image.
+    fn is_set(&self, x: i64, y: i64) -> bool {
+        match (u32::try_from(x), u32::try_from(y)) {
+            (Ok(x), Ok(y)) if x < self.width && y < self.height => {
+                self.buffer[self.row_offset(y) + x as usize]
+            }
+            _ => false,
+        }
+    }
+
+    /// See [`Perimeter::Crofton`].
+    fn crofton_perimeter(&self) -> f64 {
+        // Entries along rows, columns and both diagonals.
+        let mut entries = [0u64; 4];
+        for y in 0..i64::from(self.height) {
+            for x in 0..i64::from(self.width) {
+                if self.is_set(x, y) {
+                    let previous = [(x - 1, y), (x, y - 1), (x - 1, y - 1), (x + 1, y - 1)];
+                    for (count, (px, py)) in entries.iter_mut().zip(previous) {
+                        *count += u64::from(!self.is_set(px, py));
+                    }
+                }
+            }
+        }
+        let [rows, columns, diagonal, antidiagonal] = entries.map(|count| count as f64);
+        FRAC_PI_4 * (rows + columns + (diagonal + antidiagonal) / SQRT_2)
+    }
+
+    /// See [`Perimeter::ChainCode`], for an image with one component.
+    fn chain_code_perimeter(&self) -> f64 {
+        // Neighbors clockwise on screen, starting right.
+        const STEPS: [(i64, i64); 8] = [
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+        ];
+        let direction = |dx: i64, dy: i64| STEPS.iter().position(|&step| step == (dx, dy));
+        let Some(index) = self
+            .buffer
+            .iter()
+            .take(self.width as usize * self.height as usize)
+            .position(|set| set)
+        else {
+            return 0.0;
+        };
+        let width = self.width as usize;
+        let start = (
+            (index % width).cast_signed() as i64,
+            (index / width).cast_signed() as i64,
+        );
+
+        // Moore tracing: from each boundary pixel, search its neighbors
+        // clockwise from the clear one it was entered next to, and stop on
+        // leaving the start pixel the same way a second time.
+        let (mut pixel, mut backtrack) = (start, 4);
+        let mut first = None;
+        let mut length = 0.0;
+        loop {
+            let Some(turn) = (1..=8).find(|turn| {
+                let (dx, dy) = STEPS[(backtrack + turn) % 8];
+                self.is_set(pixel.0 + dx, pixel.1 + dy)
+            }) else {
+                return 0.0;
+            };
+            let step = (backtrack + turn) % 8;
+            if pixel == start && first == Some(step) {
+                return length;
+            }
+            first.get_or_insert(step);
+            let clear = STEPS[(backtrack + turn - 1) % 8];
+            let (dx, dy) = STEPS[step];
+            length += if step % 2 == 0 { 1.0 } else { SQRT_2 };
+            pixel = (pixel.0 + dx, pixel.1 + dy);
+            backtrack = direction(clear.0 - dx, clear.1 - dy).unwrap_or(4);
+        }
+    }
+}
+
+/// Largest distance between two vertices of a convex polygon and its smallest
+/// width, the least over its edges of the farthest vertex from the edge.
+fn feret_diameters(hull: &[(i64, i64)]) -> (f64, f64) {
+    let mut max = 0;
+    for (i, &(x0, y0)) in hull.iter().enumerate() {
+        for &(x1, y1) in &hull[i + 1..] {
+            max = max.max((x1 - x0).pow(2) + (y1 - y0).pow(2));
+        }
+    }
+    let mut min = f64::INFINITY;
+    for (&(x0, y0), &(x1, y1)) in hull.iter().zip(hull.iter().cycle().skip(1)) {
+        let farthest = hull
+            .iter()
+            .map(|&(x, y)| ((x1 - x0) * (y - y0) - (y1 - y0) * (x - x0)).abs())
+            .max()
+            .unwrap_or(0);
+        min = min.min(farthest as f64 / ((x1 - x0) as f64).hypot((y1 - y0) as f64));
+    }
+    ((max as f64).sqrt(), if min.is_finite() { min } else { 0.0 })
+}
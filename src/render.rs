@@ -0,0 +1,157 @@
+use std::fmt;
+
+use crate::BinaryImage;
+
+/// Characters used to draw pixels as text, each covering a block of pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Glyphs {
+    /// `▀`, `▄` and `█`, one column by two rows of pixels per character.
+    #[default]
+    HalfBlock,
+    /// Quadrant blocks such as `▚`, two by two pixels per character.
+    Quadrant,
+    /// Braille patterns such as `⣿`, two by four pixels per character.
+    Braille,
+}
+
+impl Glyphs {
+    /// Pixels covered by one character, as columns and rows.
+    #[must_use]
+    pub fn cell_size(self) -> (u32, u32) {
+        match self {
+            Glyphs::HalfBlock => (1, 2),
+            Glyphs::Quadrant => (2, 2),
+            Glyphs::Braille => (2, 4),
+        }
+    }
+
+    /// The character for a cell, given the set pixels as bits in row-major
+    /// order within the cell.
+    fn glyph(self, bits: u8) -> char {
+        const QUADRANTS: [char; 16] = [
+            ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+        ];
+        match self {
+            Glyphs::HalfBlock => [' ', '▀', '▄', '█'][usize::from(bits)],
+            Glyphs::Quadrant => QUADRANTS[usize::from(bits)],
+            Glyphs::Braille => {
+                // Braille numbers its dots down the left column first, with
+                // the bottom row added last.
+                const DOTS: [u8; 8] = [0x01, 0x08, 0x02, 0x10, 0x04, 0x20, 0x40, 0x80];
+                let dots = (0..8)
+                    .filter(|&bit| bits & (1 << bit) != 0)
+                    .fold(0, |dots, bit| dots | DOTS[bit]);
+                char::from_u32(0x2800 + u32::from(dots)).unwrap_or(' ')
+            }
+        }
+    }
+}
+
+/// Colors from the 256-color ANSI palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnsiColors {
+    /// Color of set pixels.
+    pub foreground: u8,
+    /// Color of clear pixels.
+    pub background: u8,
+}
+
+impl Default for AnsiColors {
+    fn default() -> Self {
+        Self {
+            foreground: 15,
+            background: 0,
+        }
+    }
+}
+
+/// Options for [`BinaryImage::render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TextOptions {
+    pub glyphs: Glyphs,
+    /// Color each line with ANSI escape codes instead of relying on the
+    /// terminal colors.
+    pub colors: Option<AnsiColors>,
+    /// Shrink the image by a whole factor until it fits in this many
+    /// characters per line. A shrunk pixel is set if any pixel it covers is,
+    /// so thin lines stay visible.
+    pub max_width: Option<u32>,
+}
+
+/// An image drawn as lines of text, see [`BinaryImage::render`].
+#[derive(Debug, Clone, Copy)]
+pub struct Rendered<'a> {
+    image: &'a BinaryImage,
+    options: TextOptions,
+}
+
+impl fmt::Display for Rendered<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (image, options) = (self.image, &self.options);
+        let (cell_width, cell_height) = options.glyphs.cell_size();
+        let columns = image.width.div_ceil(cell_width);
+        let scale = options
+            .max_width
+            .map_or(1, |max_width| columns.div_ceil(max_width.max(1)).max(1));
+        let (block_width, block_height) = (cell_width * scale, cell_height * scale);
+        let is_set = |x: u32, y: u32| {
+            // Any set pixel in the block of the shrunk pixel.
+            let (x0, y0) = (x * scale, y * scale);
+            let x1 = (x0 + scale).min(image.width);
+            (y0..(y0 + scale).min(image.height)).any(|y| {
+                let offset = image.row_offset(y);
+                (x0 as usize..x1 as usize).any(|x| image.buffer[offset + x])
+            })
+        };
+
+        let columns = image.width.div_ceil(block_width);
+        let rows = image.height.div_ceil(block_height);
+        for row in 0..rows {
+            if row > 0 {
+                f.write_str("\n")?;
+            }
+            if let Some(colors) = options.colors {
+                write!(
+                    f,
+                    "\x1b[38;5;{};48;5;{}m",
+                    colors.foreground, colors.background
+                )?;
+            }
+            for column in 0..columns {
+                let mut bits = 0;
+                for dy in 0..cell_height {
+                    for dx in 0..cell_width {
+                        let (x, y) = (column * cell_width + dx, row * cell_height + dy);
+                        if x * scale < image.width && y * scale < image.height && is_set(x, y) {
+                            bits |= 1 << (dy * cell_width + dx);
+                        }
+                    }
+                }
+                write!(f, "{}", options.glyphs.glyph(bits))?;
+            }
+            if options.colors.is_some() {
+                f.write_str("\x1b[0m")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl BinaryImage {
+    /// Draws the image as lines of text for terminals and logs, without a
+    /// trailing newline.
+    #[must_use]
+    pub fn render(&self, options: &TextOptions) -> Rendered<'_> {
+        Rendered {
+            image: self,
+            options: *options,
+        }
+    }
+}
+
+/// Draws the image with half blocks, see [`BinaryImage::render`].
+impl fmt::Display for BinaryImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(&TextOptions::default()).fmt(f)
+    }
+}
@@ -0,0 +1,419 @@
+use image::{math::Rect, GenericImageView};
+
+use crate::codecs::DecodingError;
+use crate::{bits, BinaryImage, Bit};
+
+/// A binary image stored as runs of set pixels, row by row.
+///
+/// Runs are half-open `(start, end)` column ranges, sorted and separated by at
+/// least one clear pixel, so equal images have equal runs. Memory and the cost
+/// of the operations below grow with the number of runs rather than the
+/// number of pixels, which suits sparse masks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RleImage {
+    width: u32,
+    height: u32,
+    runs: Vec<(u32, u32)>,
+    /// Index of the first run of each row, followed by the number of runs.
+    row_starts: Vec<usize>,
+}
+
+impl RleImage {
+    /// Creates an image without set pixels.
+    #[must_use]
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            runs: Vec::new(),
+            row_starts: vec![0; height as usize + 1],
+        }
+    }
+
+    /// Builds an image from the runs of each row.
+    ///
+    /// Runs may be given in any order and may overlap or touch; they are
+    /// sorted and merged. Empty runs are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more rows than `height` or a run ends past
+    /// `width`.
+    #[must_use]
+    pub fn from_rows<R, I>(width: u32, height: u32, rows: R) -> Self
+    where
+        R: IntoIterator<Item = I>,
+        I: IntoIterator<Item = (u32, u32)>,
+    {
+        let mut builder = Builder::new(width, height);
+        let mut row = Vec::new();
+        for runs in rows {
+            assert!(builder.rows() < height as usize, "Too many rows");
+            row.clear();
+            row.extend(runs.into_iter().filter(|&(start, end)| start < end));
+            assert!(
+                row.iter().all(|&(_, end)| end <= width),
+                "Run out of bounds"
+            );
+            row.sort_unstable();
+            for &(start, end) in &row {
+                builder.push(start, end);
+            }
+            builder.end_row();
+        }
+        builder.finish()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The runs of row `y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is out of bounds.
+    #[must_use]
+    pub fn row(&self, y: u32) -> &[(u32, u32)] {
+        let y = y as usize;
+        &self.runs[self.row_starts[y]..self.row_starts[y + 1]]
+    }
+
+    /// Iterates over the rows with their index.
+    pub fn rows(&self) -> impl Iterator<Item = (u32, &[(u32, u32)])> {
+        (0..self.height).map(|y| (y, self.row(y)))
+    }
+
+    /// Total number of runs.
+    #[must_use]
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Number of set pixels.
+    #[must_use]
+    pub fn area(&self) -> u64 {
+        self.runs
+            .iter()
+            .map(|&(start, end)| u64::from(end - start))
+            .sum()
+    }
+
+    /// The smallest rectangle containing all set pixels, or `None` if no pixel
+    /// is set.
+    #[must_use]
+    pub fn bounding_box(&self) -> Option<Rect> {
+        let mut rows = self.rows().filter(|(_, runs)| !runs.is_empty());
+        let (top, runs) = rows.next()?;
+        let (mut left, mut right) = (runs[0].0, runs[runs.len() - 1].1);
+        let mut bottom = top;
+        for (y, runs) in rows {
+            bottom = y;
+            left = left.min(runs[0].0);
+            right = right.max(runs[runs.len() - 1].1);
+        }
+        Some(Rect {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top + 1,
+        })
+    }
+
+    /// Pixel-wise AND of two images of the same size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ.
+    #[must_use]
+    pub fn and(&self, other: &RleImage) -> RleImage {
+        self.combine(other, |a, b| a && b)
+    }
+
+    /// Pixel-wise OR of two images of the same size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ.
+    #[must_use]
+    pub fn or(&self, other: &RleImage) -> RleImage {
+        self.combine(other, |a, b| a || b)
+    }
+
+    /// Pixel-wise XOR of two images of the same size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ.
+    #[must_use]
+    pub fn xor(&self, other: &RleImage) -> RleImage {
+        self.combine(other, |a, b| a != b)
+    }
+
+    /// Sweeps over the run boundaries of both images row by row, emitting runs
+    /// where `op` holds.
+    fn combine(&self, other: &RleImage, op: impl Fn(bool, bool) -> bool) -> RleImage {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "Dimensions mismatch"
+        );
+        let mut builder = Builder::new(self.width, self.height);
+        let boundaries = |runs: &[(u32, u32)]| {
+            runs.iter()
+                .flat_map(|&(start, end)| [start, end])
+                .collect::<Vec<_>>()
+        };
+        for y in 0..self.height {
+            let (a, b) = (boundaries(self.row(y)), boundaries(other.row(y)));
+            let (mut i, mut j) = (0, 0);
+            let mut start = None;
+            while i < a.len() || j < b.len() {
+                let position = a
+                    .get(i)
+                    .copied()
+                    .unwrap_or(u32::MAX)
+                    .min(b.get(j).copied().unwrap_or(u32::MAX));
+                while a.get(i) == Some(&position) {
+                    i += 1;
+                }
+                while b.get(j) == Some(&position) {
+                    j += 1;
+                }
+                // An odd number of boundaries passed means inside a run.
+                match (op(i % 2 == 1, j % 2 == 1), start) {
+                    (true, None) => start = Some(position),
+                    (false, Some(first)) => {
+                        builder.push(first, position);
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+            builder.end_row();
+        }
+        builder.finish()
+    }
+
+    /// Grows every run by `radius` pixels to the left and right, the dilation
+    /// with a horizontal line of `2 * radius + 1` pixels.
+    #[must_use]
+    pub fn dilate_rows(&self, radius: u32) -> RleImage {
+        let mut builder = Builder::new(self.width, self.height);
+        for (_, runs) in self.rows() {
+            for &(start, end) in runs {
+                builder.push(
+                    start.saturating_sub(radius),
+                    end.saturating_add(radius).min(self.width),
+                );
+            }
+            builder.end_row();
+        }
+        builder.finish()
+    }
+
+    /// Serializes the image compactly.
+    ///
+    /// The format is a sequence of LEB128 integers: width, height, and for
+    /// each row the number of runs followed by the gap before and the length
+    /// of each run.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.height as usize + 4 * self.runs.len());
+        write_varint(&mut bytes, self.width);
+        write_varint(&mut bytes, self.height);
+        for (_, runs) in self.rows() {
+            #[allow(clippy::cast_possible_truncation)]
+            write_varint(&mut bytes, runs.len() as u32);
+            let mut position = 0;
+            for &(start, end) in runs {
+                write_varint(&mut bytes, start - position);
+                write_varint(&mut bytes, end - start);
+                position = end;
+            }
+        }
+        bytes
+    }
+
+    /// Deserializes an image written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is truncated, has trailing bytes, or holds
+    /// runs that are empty, touch or leave the image.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodingError> {
+        let mut bytes = bytes.iter();
+        let (width, height) = (read_varint(&mut bytes)?, read_varint(&mut bytes)?);
+        if width.checked_mul(height).is_none() {
+            return Err(DecodingError::InvalidHeader("image too large".into()));
+        }
+        // Each row takes at least its run count byte, so a height beyond the
+        // remaining input is truncated and must not size any allocation.
+        if height as usize > bytes.len() {
+            return Err(DecodingError::UnexpectedEof);
+        }
+        let mut next = || read_varint(&mut bytes);
+        let invalid = || DecodingError::InvalidData("invalid run".into());
+        let mut builder = Builder::new(width, height);
+        for _ in 0..height {
+            let count = next()?;
+            let mut position = 0u32;
+            for i in 0..count {
+                let (gap, len) = (next()?, next()?);
+                if len == 0 || (i > 0 && gap == 0) {
+                    return Err(invalid());
+                }
+                let start = position.checked_add(gap).ok_or_else(invalid)?;
+                position = start
+                    .checked_add(len)
+                    .filter(|&end| end <= width)
+                    .ok_or_else(invalid)?;
+                builder.push(start, position);
+            }
+            builder.end_row();
+        }
+        if bytes.next().is_some() {
+            return Err(DecodingError::InvalidData("trailing bytes".into()));
+        }
+        Ok(builder.finish())
+    }
+}
+
+impl Default for RleImage {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+impl From<&BinaryImage> for RleImage {
+    fn from(image: &BinaryImage) -> Self {
+        let mut builder = Builder::new(image.width, image.height);
+        for y in 0..image.height {
+            bits::for_each_run(
+                &image.buffer,
+                image.row_offset(y),
+                image.width as usize,
+                |start, end| {
+                    #[allow(clippy::cast_possible_truncation)]
+                    builder.push(start as u32, end as u32);
+                },
+            );
+            builder.end_row();
+        }
+        builder.finish()
+    }
+}
+
+impl From<&RleImage> for BinaryImage {
+    fn from(image: &RleImage) -> Self {
+        let mut out = BinaryImage::new(image.width, image.height);
+        for (y, runs) in image.rows() {
+            let offset = out.row_offset(y);
+            for &(start, end) in runs {
+                bits::fill(
+                    &mut out.buffer,
+                    offset + start as usize,
+                    (end - start) as usize,
+                    true,
+                );
+            }
+        }
+        out
+    }
+}
+
+impl GenericImageView for RleImage {
+    type Pixel = Bit;
+
+    #[inline]
+    fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Looks the pixel up with a binary search over the runs of its row.
+    fn get_pixel(&self, x: u32, y: u32) -> Bit {
+        let runs = self.row(y);
+        let index = runs.partition_point(|&(start, _)| start <= x);
+        Bit(index > 0 && runs[index - 1].1 > x)
+    }
+}
+
+/// Appends runs row by row, merging runs that overlap or touch.
+struct Builder {
+    image: RleImage,
+}
+
+impl Builder {
+    fn new(width: u32, height: u32) -> Self {
+        let mut row_starts = Vec::with_capacity(height as usize + 1);
+        row_starts.push(0);
+        Self {
+            image: RleImage {
+                width,
+                height,
+                runs: Vec::new(),
+                row_starts,
+            },
+        }
+    }
+
+    /// Number of finished rows.
+    fn rows(&self) -> usize {
+        self.image.row_starts.len() - 1
+    }
+
+    /// Adds a run to the current row; runs must come sorted by start.
+    fn push(&mut self, start: u32, end: u32) {
+        let row_start = self.image.row_starts[self.rows()];
+        match self.image.runs[row_start..].last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => self.image.runs.push((start, end)),
+        }
+    }
+
+    fn end_row(&mut self) {
+        self.image.row_starts.push(self.image.runs.len());
+    }
+
+    /// Pads missing rows with empty ones.
+    fn finish(mut self) -> RleImage {
+        while self.rows() < self.image.height as usize {
+            self.end_row();
+        }
+        self.image
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    bytes.push(value as u8);
+}
+
+fn read_varint<'a>(bytes: &mut impl Iterator<Item = &'a u8>) -> Result<u32, DecodingError> {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = *bytes.next().ok_or(DecodingError::UnexpectedEof)?;
+        let bits = u32::from(byte & 0x7F);
+        if shift == 28 && bits > 0x0F {
+            break;
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(DecodingError::InvalidData("integer too large".into()))
+}
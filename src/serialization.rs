@@ -0,0 +1,112 @@
+//! `Serialize` and `Deserialize` for [`BinaryImage`].
+//!
+//! An image is a struct of `width`, `height` and `data`, the pixels in row-major
+//! order packed eight per byte with the first pixel in the most significant
+//! bit and no padding between rows. Human-readable formats get `data` as a
+//! standard base64 string, binary formats as raw bytes.
+
+use std::fmt;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{bits, BinaryImage};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "BinaryImage")]
+struct Repr {
+    width: u32,
+    height: u32,
+    data: Data,
+}
+
+/// Packed pixels, base64 encoded in human-readable formats.
+struct Data(Vec<u8>);
+
+impl Serialize for Data {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&STANDARD.encode(&self.0))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Data {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(DataVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(DataVisitor)
+        }
+    }
+}
+
+struct DataVisitor;
+
+impl<'de> de::Visitor<'de> for DataVisitor {
+    type Value = Data;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("packed pixels as bytes or a base64 string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Data, E> {
+        STANDARD.decode(value).map(Data).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Data, E> {
+        Ok(Data(value.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Data, E> {
+        Ok(Data(value))
+    }
+
+    // Some binary formats write bytes as a sequence.
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Data, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(Data(bytes))
+    }
+}
+
+impl Serialize for BinaryImage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = self.width as usize * self.height as usize;
+        let mut data = vec![0; len.div_ceil(8)];
+        bits::pack(&self.buffer, 0, len, &mut data, true, false);
+        Repr {
+            width: self.width,
+            height: self.height,
+            data: Data(data),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BinaryImage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Repr {
+            width,
+            height,
+            data: Data(data),
+        } = Repr::deserialize(deserializer)?;
+        let len = width
+            .checked_mul(height)
+            .ok_or_else(|| de::Error::custom(format!("image of {width}x{height} is too large")))?
+            as usize;
+        if data.len() != len.div_ceil(8) {
+            return Err(de::Error::invalid_length(
+                data.len(),
+                &format!("{} bytes for {width}x{height} pixels", len.div_ceil(8)).as_str(),
+            ));
+        }
+        let mut image = BinaryImage::new(width, height);
+        bits::unpack(&data, &mut image.buffer, 0, len, true, false);
+        Ok(image)
+    }
+}
@@ -0,0 +1,62 @@
+use super::*;
+use crate::binary_image;
+
+#[test]
+fn test_parse() {
+    let image: BinaryImage = r"
+        #..#
+        .##.
+        #..#
+    "
+    .parse()
+    .unwrap();
+    assert_eq!(image.dimensions(), (4, 3));
+    assert_eq!(
+        image.to_ascii(&AsciiFormat::default()),
+        "#..#\n.##.\n#..#\n"
+    );
+    assert_same(&image, &binary_image!["#..#", ".##.", "#..#"]);
+
+    let format = AsciiFormat {
+        set: 'x',
+        clear: ' ',
+    };
+    let image = BinaryImage::from_ascii("x  \n xx", &format).unwrap();
+    assert_eq!(image.to_ascii(&format), "x  \n xx\n");
+    let empty: BinaryImage = "".parse().unwrap();
+    assert_eq!(empty.dimensions(), (0, 0));
+    assert_eq!(binary_image![].dimensions(), (0, 0));
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        "##\n#".parse::<BinaryImage>().unwrap_err(),
+        AsciiError::RaggedRow {
+            row: 1,
+            expected: 2,
+            found: 1
+        }
+    );
+    let error = "#.\n#o".parse::<BinaryImage>().unwrap_err();
+    assert_eq!(
+        error,
+        AsciiError::InvalidCharacter {
+            row: 1,
+            column: 1,
+            character: 'o'
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "Unexpected character 'o' in row 1, column 1."
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let image = noise(13, 7, 5);
+    let format = AsciiFormat::default();
+    let text = image.to_ascii(&format);
+    assert_same(&image, &BinaryImage::from_ascii(&text, &format).unwrap());
+}
@@ -0,0 +1,94 @@
+use super::*;
+use crate::codecs::{bmp, DecodingError};
+
+#[test]
+fn test_encode() {
+    let mut data = Vec::new();
+    BinaryImage::from_raw(4, 4, &DATA)
+        .write_bmp(&mut data)
+        .unwrap();
+
+    assert_eq!(data.len(), 62 + 4 * 4);
+    assert_eq!(&data[..2], b"BM");
+    assert_eq!(data[2], 78);
+    assert_eq!(data[10], 62);
+    assert_eq!(&data[54..62], &[0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0]);
+    // Bottom row first, first pixel in the most significant bit.
+    assert_eq!(
+        &data[62..],
+        &[0x10, 0, 0, 0, 0x40, 0, 0, 0, 0x60, 0, 0, 0, 0x80, 0, 0, 0]
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    for (width, height) in [(1, 1), (31, 3), (32, 2), (75, 9)] {
+        let image = noise(width, height, width);
+        let mut data = Vec::new();
+        image.write_bmp(&mut data).unwrap();
+        assert_same(&bmp::decode(data.as_slice()).unwrap(), &image);
+    }
+}
+
+#[test]
+fn test_decode_variants() {
+    let image = BinaryImage::from_raw(4, 4, &DATA);
+    let mut data = Vec::new();
+    image.write_bmp(&mut data).unwrap();
+
+    // Top-down rows with an inverted palette.
+    let mut top_down = data.clone();
+    top_down[22..26].copy_from_slice(&(-4i32).to_le_bytes());
+    top_down[54..62].copy_from_slice(&[0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0]);
+    let rows: Vec<u8> = data[62..].chunks(4).rev().flatten().copied().collect();
+    top_down[62..].copy_from_slice(&rows);
+    let decoded = bmp::decode(top_down.as_slice()).unwrap();
+    assert_same(&decoded, &image.inverted());
+
+    // OS/2 core header with three-byte palette entries.
+    let mut core = b"BM\0\0\0\0\0\0\0\0\x20\0\0\0".to_vec();
+    core.extend_from_slice(&[12, 0, 0, 0, 4, 0, 4, 0, 1, 0, 1, 0]);
+    core.extend_from_slice(&[0, 0, 0, 0xFF, 0xFF, 0xFF]);
+    core.extend_from_slice(&data[62..]);
+    assert_same(&bmp::decode(core.as_slice()).unwrap(), &image);
+}
+
+#[test]
+fn test_malformed() {
+    let mut data = Vec::new();
+    BinaryImage::new(8, 8).write_bmp(&mut data).unwrap();
+
+    assert!(matches!(
+        bmp::decode(&data[..data.len() - 1]),
+        Err(DecodingError::UnexpectedEof)
+    ));
+    // A huge height fails on the missing rows instead of allocating for them.
+    let mut huge = data.clone();
+    huge[22..26].copy_from_slice(&(1i32 << 24).to_le_bytes());
+    assert!(matches!(
+        bmp::decode(huge.as_slice()),
+        Err(DecodingError::UnexpectedEof)
+    ));
+    assert!(matches!(
+        bmp::decode(&b"GIF89a"[..]),
+        Err(DecodingError::UnexpectedEof)
+    ));
+    assert!(matches!(
+        bmp::decode(&b"PNG\r\n\x1a\n\0\0\0\0\0\0\0\0\0"[..]),
+        Err(DecodingError::InvalidHeader(_))
+    ));
+
+    let mut rgb = data.clone();
+    rgb[28] = 24;
+    assert!(matches!(
+        bmp::decode(rgb.as_slice()),
+        Err(DecodingError::Unsupported(_))
+    ));
+
+    let mut rle = data;
+    rle[30] = 1;
+    assert!(matches!(
+        bmp::decode(rle.as_slice()),
+        Err(DecodingError::Unsupported(_))
+    ));
+}
@@ -0,0 +1,97 @@
+use super::*;
+use crate::codecs::ccitt::{self, CcittOptions};
+use crate::codecs::DecodingError;
+
+/// One row: three white, two black and three white pixels.
+fn row() -> BinaryImage {
+    BinaryImage::from_raw(8, 1, &[1, 1, 1, 0, 0, 1, 1, 1])
+}
+
+#[test]
+fn test_encode_known() {
+    let one_dimensional = CcittOptions {
+        end_of_block: false,
+        ..CcittOptions::default()
+    };
+    // White 3, black 2, white 3.
+    assert_eq!(row().to_ccitt(&one_dimensional), [0b1000_1110, 0b0000_0000]);
+    // Horizontal with white 3 and black 2, then V0.
+    assert_eq!(
+        row().to_ccitt(&CcittOptions::group4()),
+        [0b0011_0001, 0b1100_0000]
+    );
+    // Inverted colors swap the codes.
+    let black_is_1 = CcittOptions {
+        black_is_1: true,
+        ..one_dimensional
+    };
+    assert_eq!(
+        row().inverted().materialize().to_ccitt(&black_is_1),
+        [0b1000_1110, 0b0000_0000]
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let image = noise(173, 21, 7);
+    let mut options = Vec::new();
+    for k in [-1, 0, 1, 4] {
+        for (end_of_line, encoded_byte_align, end_of_block, black_is_1) in [
+            (false, false, false, false),
+            (true, false, true, false),
+            (false, true, true, true),
+            (true, true, false, false),
+        ] {
+            options.push(CcittOptions {
+                k,
+                end_of_line,
+                encoded_byte_align,
+                end_of_block,
+                black_is_1,
+            });
+        }
+    }
+    for options in &options {
+        let data = image.to_ccitt(options);
+        let decoded = ccitt::decode(&data, 173, Some(21), options).unwrap();
+        assert_same(&decoded, &image);
+        let decoded = ccitt::decode(&data, 173, None, options).unwrap();
+        assert_same(&decoded, &image);
+    }
+}
+
+#[test]
+fn test_long_runs() {
+    let mut image = BinaryImage::new(6000, 3);
+    image.sub_view_mut(2600, 1, 3000, 1).fill(Bit(true));
+    image.put_pixel(5999, 2, Bit(true));
+    for options in [CcittOptions::group3(2), CcittOptions::group4()] {
+        let data = image.to_ccitt(&options);
+        assert_same(&ccitt::decode(&data, 6000, None, &options).unwrap(), &image);
+    }
+}
+
+#[test]
+fn test_malformed() {
+    let options = CcittOptions::group4();
+    let data = noise(40, 8, 3).to_ccitt(&options);
+
+    assert!(matches!(
+        ccitt::decode(&data[..data.len() / 2], 40, Some(8), &options),
+        Err(DecodingError::UnexpectedEof)
+    ));
+    // A row of 8 white pixels is too long for a width of 4.
+    assert!(matches!(
+        ccitt::decode(&[0b1001_1000], 4, Some(1), &CcittOptions::default()),
+        Err(DecodingError::InvalidData(_))
+    ));
+    assert!(matches!(
+        ccitt::decode(&[0, 0x20], 8, Some(1), &CcittOptions::default()),
+        Err(DecodingError::InvalidData(_))
+    ));
+    // Uncompressed mode extension.
+    assert!(matches!(
+        ccitt::decode(&[0b0000_0011, 0b1100_0000], 8, Some(1), &options),
+        Err(DecodingError::Unsupported(_))
+    ));
+}
@@ -0,0 +1,88 @@
+#![allow(clippy::cast_precision_loss)]
+
+use super::*;
+use crate::codecs::coco::{self, CocoRle};
+use crate::codecs::DecodingError;
+
+#[test]
+fn test_encode() {
+    let image = BinaryImage::from_raw(3, 2, &[1u8, 0, 0, 1, 1, 0]);
+    let rle = CocoRle::from(&image);
+    assert_eq!(rle.counts(), &[0, 2, 1, 1, 2]);
+    assert_eq!(rle.to_compressed(), "021O1");
+    assert_eq!(rle.area(), 3);
+    assert_eq!(rle.to_bbox(), [0, 0, 2, 2]);
+    assert_same(&BinaryImage::from(&rle), &image);
+
+    assert_eq!(CocoRle::from(&BinaryImage::new(4, 4)).counts(), &[16]);
+    assert_eq!(CocoRle::from(&BinaryImage::new(4, 4)).to_bbox(), [0; 4]);
+}
+
+#[test]
+fn test_compressed() {
+    // Large runs take several characters, deltas may be negative.
+    let rle = CocoRle::from_counts(20, 10, vec![10, 100, 50, 2, 38]).unwrap();
+    assert_eq!(rle.to_compressed(), ":T3b1nLD");
+    assert_eq!(CocoRle::from_compressed(20, 10, ":T3b1nLD").unwrap(), rle);
+    // A run wrapping into the next column spans all rows.
+    assert_eq!(rle.to_bbox(), [1, 0, 16, 10]);
+
+    for (width, height) in [(1, 1), (33, 7), (64, 40)] {
+        let image = noise(width, height, height);
+        let rle = CocoRle::from(&image);
+        let decoded = CocoRle::from_compressed(width, height, &rle.to_compressed()).unwrap();
+        assert_eq!(decoded, rle);
+        assert_same(&BinaryImage::from(&decoded), &image);
+    }
+}
+
+#[test]
+fn test_invalid() {
+    assert!(matches!(
+        CocoRle::from_counts(2, 2, vec![1, 2]),
+        Err(DecodingError::InvalidData(_))
+    ));
+    assert!(matches!(
+        CocoRle::from_compressed(2, 2, "0 4"),
+        Err(DecodingError::InvalidData(_))
+    ));
+    assert!(matches!(
+        CocoRle::from_compressed(2, 2, "0T"),
+        Err(DecodingError::UnexpectedEof)
+    ));
+}
+
+#[test]
+fn test_iou() {
+    let (a, b) = (noise(45, 30, 5), noise(45, 30, 6));
+    let (rle_a, rle_b) = (CocoRle::from(&a), CocoRle::from(&b));
+    let count = |op: fn(bool, bool) -> bool| {
+        a.pixels()
+            .filter(|&(x, y, pixel)| op(*pixel, *b.get_pixel(x, y)))
+            .count() as u64
+    };
+
+    assert_eq!(rle_a.area(), count(|a, _| a));
+    assert_eq!(rle_a.intersection_area(&rle_b), count(|a, b| a && b));
+    let iou = rle_a.iou(&rle_b);
+    assert!((iou - count(|a, b| a && b) as f64 / count(|a, b| a || b) as f64).abs() < 1e-12);
+    assert!((rle_a.iou(&rle_a) - 1.0).abs() < 1e-12);
+    assert!(
+        CocoRle::from(&BinaryImage::new(3, 3)).iou(&CocoRle::from(&BinaryImage::new(3, 3))) == 0.0
+    );
+
+    let bbox = a.bounding_box().unwrap();
+    assert_eq!(rle_a.to_bbox(), [bbox.x, bbox.y, bbox.width, bbox.height]);
+}
+
+#[test]
+fn test_polygons() {
+    // A ring: the hole is not part of the polygons.
+    let mut image = BinaryImage::new(5, 4);
+    image.sub_view_mut(1, 1, 3, 3).fill(Bit(true));
+    image.put_pixel(2, 2, Bit(false));
+    assert_eq!(
+        coco::polygons(&image),
+        vec![vec![1.0, 1.0, 4.0, 1.0, 4.0, 4.0, 1.0, 4.0]]
+    );
+}
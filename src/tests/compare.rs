@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use bit_vec::BitVec;
+
+use super::*;
+use crate::{assert_binary_eq, binary_image};
+
+#[test]
+fn test_eq_and_hash() {
+    let image = binary_image!["#.#", ".#."];
+    // Trailing storage past the pixels is ignored.
+    let mut bits = BitVec::from_elem(40, true);
+    for i in [1, 3, 5] {
+        bits.set(i, false);
+    }
+    let padded = BinaryImage::from_bitvec(3, 2, bits);
+    assert_eq!(image, padded);
+    assert_ne!(image, binary_image!["#.#", "..."]);
+    assert_ne!(image, binary_image!["#.", ".#", "#."]);
+
+    let set: HashSet<_> = [image.clone(), padded].into_iter().collect();
+    assert_eq!(set.len(), 1);
+    assert!(set.contains(&image));
+}
+
+#[test]
+fn test_diff() {
+    let actual = noise(45, 7, 3);
+    let expected = noise(45, 7, 4);
+    let diff = diff(&actual, &expected);
+    let (mut false_positives, mut false_negatives) = (0, 0);
+    for (x, y, pixel) in actual.pixels() {
+        let other = *expected.get_pixel(x, y);
+        assert_eq!(*diff.mask.get_pixel(x, y), *pixel != other);
+        false_positives += u64::from(*pixel && !other);
+        false_negatives += u64::from(!*pixel && other);
+    }
+    assert_eq!(
+        (diff.false_positives, diff.false_negatives),
+        (false_positives, false_negatives)
+    );
+    assert!(!diff.is_empty());
+    assert!(super::diff(&actual, &actual).is_empty());
+}
+
+#[test]
+fn test_assert_binary_eq() {
+    let left = binary_image!["....", ".##.", "...."];
+    assert_binary_eq!(left, left.clone());
+
+    let right = binary_image!["....", "..##", "...."];
+    let message = std::panic::catch_unwind(|| assert_binary_eq!(left, right, "case {}", 1))
+        .unwrap_err()
+        .downcast::<String>()
+        .unwrap();
+    assert!(message.starts_with("assertion `left == right` failed: case 1\n"));
+    assert!(message.contains(
+        "2 pixels differ, 1 set only left and 1 only right, in columns 1..4 and rows 1..2:"
+    ));
+    assert!(
+        message.ends_with("left  | right | diff\n##.   | .##   | +.-\n"),
+        "{message}"
+    );
+}
@@ -0,0 +1,64 @@
+use super::*;
+
+#[test]
+fn test_outlines() {
+    let mut image = BinaryImage::new(4, 4);
+    image.put_pixel(1, 2, Bit(true));
+    let outlines = image.outlines(Connectivity::Eight);
+    assert_eq!(outlines.len(), 1);
+    assert_eq!(outlines[0].points, vec![(1, 2), (2, 2), (2, 3), (1, 3)]);
+    assert_eq!(outlines[0].signed_area(), 1);
+    assert_eq!(outlines[0].perimeter(), 4);
+
+    // A ring with a hole.
+    let mut ring = BinaryImage::new(5, 5);
+    ring.sub_view_mut(1, 1, 3, 3).fill(Bit(true));
+    ring.put_pixel(2, 2, Bit(false));
+    let outlines = ring.outlines(Connectivity::Four);
+    assert_eq!(outlines.len(), 2);
+    assert_eq!(outlines[0].signed_area(), 9);
+    assert!(outlines[1].is_hole());
+    assert_eq!(outlines[1].points, vec![(2, 2), (2, 3), (3, 3), (3, 2)]);
+}
+
+#[test]
+fn test_connectivity() {
+    let image = BinaryImage::from_raw(2, 2, &[1u8, 0, 0, 1]);
+    let joined = image.outlines(Connectivity::Eight);
+    assert_eq!(joined.len(), 1);
+    assert_eq!(joined[0].points.len(), 8);
+    assert_eq!(image.outlines(Connectivity::Four).len(), 2);
+
+    // The diagonal gap inside a ring of 4-connected pixels is a hole only
+    // when the pixels are not joined at corners.
+    let diamond = BinaryImage::from_raw(3, 3, &[0u8, 1, 0, 1, 0, 1, 0, 1, 0]);
+    assert_eq!(diamond.outlines(Connectivity::Eight).len(), 2);
+    assert_eq!(diamond.outlines(Connectivity::Four).len(), 4);
+}
+
+#[test]
+fn test_even_odd() {
+    for connectivity in [Connectivity::Four, Connectivity::Eight] {
+        let image = noise(37, 23, 9);
+        let outlines = image.outlines(connectivity);
+        let count = image.pixels().filter(|(_, _, pixel)| **pixel).count();
+        let area: i64 = outlines.iter().map(Outline::signed_area).sum();
+        assert_eq!(area, i64::try_from(count).unwrap());
+        // Every boundary edge belongs to exactly one outline.
+        let edges = image
+            .pixels()
+            .filter(|(_, _, pixel)| **pixel)
+            .map(|(x, y, _)| {
+                [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                    .into_iter()
+                    .filter(|&(dx, dy)| {
+                        let (x, y) = (x.cast_signed() + dx, y.cast_signed() + dy);
+                        !image.in_bounds(x.cast_unsigned(), y.cast_unsigned())
+                            || !*image.get_pixel(x.cast_unsigned(), y.cast_unsigned())
+                    })
+                    .count() as u64
+            })
+            .sum::<u64>();
+        assert_eq!(outlines.iter().map(Outline::perimeter).sum::<u64>(), edges);
+    }
+}
@@ -0,0 +1,107 @@
+#![allow(clippy::cast_precision_loss)]
+
+use super::*;
+
+#[test]
+fn test_squared_distance_transform() {
+    for (width, height, seed) in [(17, 9, 1), (1, 12, 2), (33, 1, 3), (20, 20, 4)] {
+        let mut image = noise(width, height, seed);
+        // Sparse set pixels give longer distances.
+        for (x, y, pixel) in noise(width, height, seed + 10).pixels() {
+            if *pixel {
+                image.put_pixel(x, y, Bit(false));
+            }
+        }
+        let set: Vec<_> = image.pixels().filter(|(_, _, p)| **p).collect();
+        let transform = image.squared_distance_transform();
+        for (x, y, _) in image.pixels() {
+            let expected = set
+                .iter()
+                .map(|&(sx, sy, _)| u64::from(sx.abs_diff(x).pow(2) + sy.abs_diff(y).pow(2)))
+                .min()
+                .unwrap_or(u64::MAX);
+            assert_eq!(
+                transform[(y * width + x) as usize],
+                expected,
+                "at ({x}, {y})"
+            );
+        }
+    }
+    assert!(BinaryImage::new(3, 2)
+        .squared_distance_transform()
+        .iter()
+        .all(|&d| d == u64::MAX));
+}
+
+#[test]
+fn test_shape_distances() {
+    let a = noise(23, 17, 7);
+    let mut b = BinaryImage::new(23, 17);
+    b.sub_view_mut(3, 4, 5, 6).fill(Bit(true));
+
+    let points = |image: &BinaryImage| -> Vec<(f64, f64)> {
+        image
+            .pixels()
+            .filter(|(_, _, p)| **p)
+            .map(|(x, y, _)| (f64::from(x), f64::from(y)))
+            .collect()
+    };
+    let nearest = |from: &[(f64, f64)], to: &[(f64, f64)]| -> Vec<f64> {
+        from.iter()
+            .map(|&(x, y)| {
+                to.iter()
+                    .map(|&(tx, ty)| (x - tx).hypot(y - ty))
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect()
+    };
+    let (pa, pb) = (points(&a), points(&b));
+    let forward = nearest(&pa, &pb);
+    let backward = nearest(&pb, &pa);
+    let max = |d: &[f64]| d.iter().copied().fold(0.0, f64::max);
+    let mean = |d: &[f64]| d.iter().sum::<f64>() / d.len() as f64;
+
+    let distances = a.shape_distances(&b);
+    assert!((distances.directed_hausdorff - max(&forward)).abs() < 1e-9);
+    assert!((distances.reverse_hausdorff - max(&backward)).abs() < 1e-9);
+    assert!((distances.hausdorff - a.hausdorff(&b)).abs() < 1e-9);
+    assert!((distances.chamfer - f64::midpoint(mean(&forward), mean(&backward))).abs() < 1e-9);
+    let mut all: Vec<_> = forward.iter().chain(&backward).copied().collect();
+    all.sort_by(f64::total_cmp);
+    assert!(distances.hausdorff95 <= distances.hausdorff);
+    assert!(distances.hausdorff95 >= all[all.len() * 9 / 10]);
+}
+
+#[test]
+fn test_hausdorff() {
+    let mut a = BinaryImage::new(10, 10);
+    a.put_pixel(1, 1, Bit(true));
+    let mut b = a.clone();
+    b.put_pixel(4, 5, Bit(true));
+    assert!(a.directed_hausdorff(&b).abs() < f64::EPSILON);
+    assert!((b.directed_hausdorff(&a) - 5.0).abs() < f64::EPSILON);
+    assert!((a.hausdorff(&b) - 5.0).abs() < f64::EPSILON);
+
+    // Of the distances 0, 0 and 5, the 95th percentile is interpolated.
+    let distances = a.shape_distances(&b);
+    assert!((distances.hausdorff95 - 4.5).abs() < 1e-9);
+    assert!((distances.chamfer - 1.25).abs() < 1e-9);
+
+    let empty = BinaryImage::new(10, 10);
+    assert!(a.hausdorff(&empty).is_infinite());
+    assert!(empty.shape_distances(&empty).hausdorff.abs() < f64::EPSILON);
+    assert!(a.shape_distances(&empty).hausdorff95.is_infinite());
+
+    // Only the directed distance from the empty image is finite.
+    let distances = empty.shape_distances(&a);
+    assert!(distances.directed_hausdorff.abs() < f64::EPSILON);
+    assert!(empty.directed_hausdorff(&a).abs() < f64::EPSILON);
+    for distance in [
+        distances.reverse_hausdorff,
+        distances.hausdorff,
+        distances.hausdorff95,
+        distances.chamfer,
+    ] {
+        assert!(distance.is_infinite());
+    }
+}
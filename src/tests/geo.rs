@@ -0,0 +1,101 @@
+use super::*;
+use crate::codecs::geo::{self, GeoOptions, Ring};
+
+fn twice_area(ring: &Ring) -> f64 {
+    ring.windows(2)
+        .map(|pair| pair[0].0 * pair[1].1 - pair[1].0 * pair[0].1)
+        .sum()
+}
+
+#[test]
+fn test_wkt() {
+    let mut image = BinaryImage::new(3, 3);
+    image.put_pixel(1, 1, Bit(true));
+    assert_eq!(
+        image.to_wkt(&GeoOptions::default()),
+        ["POLYGON ((1 1, 2 1, 2 2, 1 2, 1 1))"]
+    );
+
+    // North-up, 10 units per pixel.
+    let options = GeoOptions {
+        transform: [500.0, 10.0, 0.0, 2000.0, 0.0, -10.0],
+        ..GeoOptions::default()
+    };
+    assert_eq!(
+        image.to_wkt(&options),
+        ["POLYGON ((510 1990, 510 1980, 520 1980, 520 1990, 510 1990))"]
+    );
+}
+
+#[test]
+fn test_holes() {
+    // A frame around a single pixel, next to a square with two holes.
+    let mut image = BinaryImage::new(12, 6);
+    image.sub_view_mut(0, 0, 5, 5).fill(Bit(true));
+    image.sub_view_mut(1, 1, 3, 3).fill(Bit(false));
+    image.put_pixel(2, 2, Bit(true));
+    image.sub_view_mut(6, 0, 5, 5).fill(Bit(true));
+    image.sub_view_mut(7, 1, 2, 2).fill(Bit(false));
+    image.put_pixel(9, 3, Bit(false));
+    image.put_pixel(11, 5, Bit(true));
+
+    for transform in [
+        [0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        [100.0, 0.5, 0.0, 50.0, 0.0, -0.5],
+        [0.0, 1.0, 0.2, 0.0, 0.3, 1.0],
+    ] {
+        let options = GeoOptions {
+            transform,
+            ..GeoOptions::default()
+        };
+        let components = geo::polygons(&image, &options);
+        let counts: Vec<_> = components
+            .iter()
+            .map(|polygons| {
+                polygons
+                    .iter()
+                    .map(|polygon| polygon.holes.len())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        // The holes and the pixel at (11, 5) touch at corners only.
+        assert_eq!(counts, [vec![1], vec![2, 0], vec![0]]);
+        for polygon in components.iter().flatten() {
+            for (ring, exterior) in std::iter::once((&polygon.exterior, true))
+                .chain(polygon.holes.iter().map(|hole| (hole, false)))
+            {
+                assert_eq!(ring.first(), ring.last());
+                let mut points: Vec<_> = ring[1..]
+                    .iter()
+                    .map(|&(x, y)| (x.to_bits(), y.to_bits()))
+                    .collect();
+                points.sort_unstable();
+                points.dedup();
+                assert_eq!(points.len(), ring.len() - 1, "Ring touches itself");
+                assert_eq!(twice_area(ring) > 0.0, exterior);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_geojson() {
+    let mut image = BinaryImage::new(4, 2);
+    image.put_pixel(0, 0, Bit(true));
+    image.put_pixel(1, 1, Bit(true));
+    image.put_pixel(3, 0, Bit(true));
+
+    let json = image.to_geojson(&GeoOptions::default());
+    assert!(json.starts_with(r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{"label":1},"geometry":{"type":"MultiPolygon","coordinates":[[[[0,0],[1,0],[1,1],[0,1],[0,0]]],[[[1,1],[2,1],[2,2],[1,2],[1,1]]]]}}"#), "{json}");
+    assert!(json.ends_with(r#"{"type":"Feature","properties":{"label":2},"geometry":{"type":"Polygon","coordinates":[[[3,0],[4,0],[4,1],[3,1],[3,0]]]}}]}"#), "{json}");
+
+    let json = image.to_geojson(&GeoOptions {
+        connectivity: Connectivity::Four,
+        ..GeoOptions::default()
+    });
+    assert_eq!(json.matches(r#""type":"Polygon""#).count(), 3);
+    assert_eq!(
+        BinaryImage::new(2, 2).to_geojson(&GeoOptions::default()),
+        r#"{"type":"FeatureCollection","features":[]}"#
+    );
+}
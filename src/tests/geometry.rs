@@ -0,0 +1,99 @@
+use super::*;
+
+fn pixels(image: &BinaryImage) -> Vec<u8> {
+    image
+        .pixels()
+        .map(|(_, _, pixel)| u8::from(*pixel))
+        .collect()
+}
+
+#[test]
+fn test_crop() {
+    let image = BinaryImage::from_raw(4, 4, &DATA);
+    let cropped = image.crop(1, 1, 2, 3);
+
+    assert_eq!(cropped.dimensions(), (2, 3));
+    assert_eq!(pixels(&cropped), [1, 1, 1, 0, 0, 0]);
+
+    let clamped = image.crop(3, 2, 10, 10);
+    assert_eq!(clamped.dimensions(), (1, 2));
+    assert_eq!(pixels(&clamped), [0, 1]);
+}
+
+#[test]
+fn test_crop_across_words() {
+    let image = BinaryImage::from_raw(70, 3, &[1u8; 210]);
+    let cropped = image.crop(3, 1, 65, 2);
+
+    assert_eq!(cropped.dimensions(), (65, 2));
+    assert!(cropped.pixels().all(|(_, _, pixel)| *pixel));
+}
+
+#[test]
+fn test_pad() {
+    let image = BinaryImage::from_raw(4, 4, &DATA);
+    let padded = image.pad(1, 0, 0, 1, Bit(true));
+
+    assert_eq!(padded.dimensions(), (5, 5));
+    assert_eq!(
+        pixels(&padded),
+        [
+            1, 1, 0, 0, 0, //
+            1, 0, 1, 1, 0, //
+            1, 0, 1, 0, 0, //
+            1, 0, 0, 0, 1, //
+            1, 1, 1, 1, 1, //
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Padded image too large")]
+fn test_pad_overflow() {
+    let _ = BinaryImage::new(4, 1).pad(0, 0, u32::MAX - 3, 0, Bit(false));
+}
+
+#[test]
+fn test_shift() {
+    let mut image = BinaryImage::from_raw(4, 4, &DATA);
+    image.shift(1, 1, Bit(false));
+    assert_eq!(
+        pixels(&image),
+        [
+            0, 0, 0, 0, //
+            0, 1, 0, 0, //
+            0, 0, 1, 1, //
+            0, 0, 1, 0, //
+        ]
+    );
+
+    image.shift(-2, -1, Bit(true));
+    assert_eq!(
+        pixels(&image),
+        [
+            0, 0, 1, 1, //
+            1, 1, 1, 1, //
+            1, 0, 1, 1, //
+            1, 1, 1, 1, //
+        ]
+    );
+
+    image.shift(0, 5, Bit(false));
+    assert_eq!(pixels(&image), [0; 16]);
+}
+
+#[test]
+fn test_trim() {
+    let image = BinaryImage::from_raw(4, 4, &DATA).crop(1, 1, 3, 3);
+    let (rect, trimmed) = image.trim().unwrap();
+
+    assert_eq!((rect.x, rect.y, rect.width, rect.height), (0, 0, 3, 3));
+    assert_eq!(pixels(&trimmed), pixels(&image));
+
+    let image = BinaryImage::from_raw(4, 4, &DATA).crop(0, 1, 3, 2);
+    let (rect, trimmed) = image.trim().unwrap();
+    assert_eq!((rect.x, rect.y, rect.width, rect.height), (1, 0, 2, 2));
+    assert_eq!(pixels(&trimmed), [1, 1, 1, 0]);
+
+    assert!(BinaryImage::new(5, 5).trim().is_none());
+}
@@ -0,0 +1,93 @@
+use super::*;
+use crate::binary_image;
+
+/// Sparse pseudo-random pixels, so hulls have many slanted edges.
+fn scatter(width: u32, height: u32, seed: u32) -> BinaryImage {
+    let dense = noise(width, height, seed);
+    let mut image = BinaryImage::new(width, height);
+    for (x, y, pixel) in dense.pixels() {
+        if *pixel && (x * 7 + y * 3) % 11 == 0 {
+            image.put_pixel(x, y, Bit(true));
+        }
+    }
+    image
+}
+
+fn cross(o: (u32, u32), a: (u32, u32), b: (i64, i64)) -> i64 {
+    let (o, a) = (
+        (i64::from(o.0), i64::from(o.1)),
+        (i64::from(a.0), i64::from(a.1)),
+    );
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Whether a point, in doubled coordinates, is inside or on a clockwise hull.
+fn inside(hull: &[(u32, u32)], point: (i64, i64)) -> bool {
+    let double = |(x, y): (u32, u32)| (2 * x, 2 * y);
+    hull.iter()
+        .zip(hull.iter().cycle().skip(1))
+        .all(|(&a, &b)| cross(double(a), double(b), point) >= 0)
+}
+
+#[test]
+fn test_convex_hull() {
+    let image = scatter(45, 31, 3);
+    let hull = image.convex_hull();
+    assert!(
+        Outline {
+            points: hull.clone()
+        }
+        .signed_area()
+            > 0
+    );
+    for (&a, (&b, &c)) in hull
+        .iter()
+        .zip(hull.iter().cycle().skip(1).zip(hull.iter().cycle().skip(2)))
+    {
+        // Strictly convex, clockwise on screen.
+        assert!(cross(a, b, (i64::from(c.0), i64::from(c.1))) > 0);
+    }
+    for (x, y, pixel) in image.pixels() {
+        let corners = [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)];
+        if *pixel {
+            assert!(corners
+                .iter()
+                .all(|&(cx, cy)| inside(&hull, (2 * i64::from(cx), 2 * i64::from(cy)))));
+        }
+    }
+    assert!(BinaryImage::new(5, 5).convex_hull().is_empty());
+    let dot = BinaryImage::from_raw(3, 3, &[0u8, 0, 0, 0, 1, 0, 0, 0, 0]);
+    assert_eq!(dot.convex_hull(), [(1, 1), (2, 1), (2, 2), (1, 2)]);
+}
+
+#[test]
+fn test_convex_hulls() {
+    let image = noise(40, 40, 8);
+    let components = image.components(Connectivity::Eight);
+    let hulls = components.convex_hulls();
+    assert_eq!(hulls.len(), components.count() as usize);
+    for (label, hull) in (1..).zip(&hulls) {
+        assert_eq!(hull, &components.mask(label).convex_hull());
+    }
+}
+
+#[test]
+fn test_convex_hull_image() {
+    let image = scatter(37, 23, 5);
+    let hull = image.convex_hull();
+    let filled = image.convex_hull_image();
+    for (x, y, pixel) in filled.pixels() {
+        let center = (2 * i64::from(x) + 1, 2 * i64::from(y) + 1);
+        assert_eq!(*pixel, inside(&hull, center), "({x}, {y})");
+    }
+
+    let line = binary_image!["#....", ".#...", "..#..", "...#.",];
+    crate::assert_binary_eq!(
+        line.convex_hull_image(),
+        binary_image!["##...", "###..", ".###.", "..##.",]
+    );
+    assert_eq!(
+        BinaryImage::new(3, 2).convex_hull_image(),
+        BinaryImage::new(3, 2)
+    );
+}
@@ -0,0 +1,242 @@
+use super::*;
+use crate::binary_image;
+use crate::codecs::jbig2::{self, Context, GenericRegionParams, MqDecoder, MqEncoder};
+use crate::codecs::DecodingError;
+
+/// Test sequence of T.88 H.2, coded with a single context.
+const MQ_INPUT: [u8; 32] = [
+    0x00, 0x02, 0x00, 0x51, 0x00, 0x00, 0x00, 0xC0, 0x03, 0x52, 0x87, 0x2A, 0xAA, 0xAA, 0xAA, 0xAA,
+    0x82, 0xC0, 0x20, 0x00, 0xFC, 0xD7, 0x9E, 0xF6, 0xBF, 0x7F, 0xED, 0x90, 0x4F, 0x46, 0xA3, 0xBF,
+];
+const MQ_OUTPUT: [u8; 30] = [
+    0x84, 0xC7, 0x3B, 0xFC, 0xE1, 0xA1, 0x43, 0x04, 0x02, 0x20, 0x00, 0x00, 0x41, 0x0D, 0xBB, 0x86,
+    0xF4, 0x31, 0x7F, 0xFF, 0x88, 0xFF, 0x37, 0x47, 0x1A, 0xDB, 0x6A, 0xDF, 0xFF, 0xAC,
+];
+
+/// Generic region data of T.88 H.2, segment 4, coded with MMR.
+const ANNEX_H_MMR: [u8; 26] = [
+    0x26, 0xA0, 0x71, 0xCE, 0xA7, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xF8, 0xF0,
+];
+/// Generic region data of T.88 H.2, segment 11, coded with template 0,
+/// typical prediction and adaptive pixels (3, -1), (-3, -1), (2, -2) and
+/// (-2, -2).
+const ANNEX_H_ARITHMETIC: [u8; 9] = [0x04, 0xEE, 0xED, 0x87, 0xFB, 0xCB, 0x2B, 0xFF, 0xAC];
+
+/// The 54×44 bitmap of both generic regions of T.88 H.2: a black frame two
+/// pixels wide, so clear pixels around set ones.
+fn annex_h_bitmap() -> BinaryImage {
+    let mut image = BinaryImage::new(54, 44);
+    image.sub_view_mut(2, 2, 50, 40).fill(Bit(true));
+    image
+}
+
+/// Generic regions of `reference_bitmap()` for each template, without and with
+/// typical prediction, using the nominal adaptive pixels. Checked to decode
+/// to the same bitmap with jbig2dec.
+const REFERENCE_REGIONS: [(u8, bool, &[u8]); 8] = [
+    (
+        0,
+        false,
+        &[
+            0x05, 0x60, 0xE3, 0x12, 0x8D, 0xF4, 0xB9, 0xE1, 0x1A, 0x9C, 0xD1, 0xE4, 0x07, 0x9E,
+            0x4A, 0x38, 0x62, 0xF2, 0x51, 0xC3, 0x2A, 0xE5, 0xB0, 0xD8, 0xFF, 0x62, 0xA4, 0x49,
+            0x86, 0x64, 0x1F, 0x81, 0x87, 0x85, 0x11, 0x1D, 0xAB, 0xFE, 0x4C, 0x15, 0xED, 0x9E,
+            0x7E, 0x1A, 0xFF, 0xAC,
+        ],
+    ),
+    (
+        0,
+        true,
+        &[
+            0x58, 0xB1, 0x71, 0x89, 0x60, 0x8E, 0x2E, 0x78, 0xA7, 0xD6, 0x9A, 0x3E, 0x33, 0x95,
+            0xE4, 0xA4, 0x77, 0xA1, 0x34, 0x5D, 0x93, 0xC4, 0x41, 0x88, 0x13, 0xCC, 0x8E, 0xD7,
+            0x5F, 0xA3, 0x05, 0x85, 0x67, 0xDF, 0x86, 0xBF, 0xFF, 0xAC,
+        ],
+    ),
+    (
+        1,
+        false,
+        &[
+            0x05, 0xC9, 0xAA, 0x81, 0x6B, 0x2A, 0x3B, 0x84, 0x39, 0x39, 0xA3, 0xC8, 0x0F, 0x3C,
+            0x94, 0x71, 0x1D, 0x45, 0x1A, 0x50, 0xE5, 0xBA, 0x65, 0xBC, 0xA6, 0x6D, 0x6F, 0x08,
+            0x04, 0xAD, 0x02, 0x0C, 0x72, 0x1D, 0x57, 0x10, 0xC7, 0xF4, 0x60, 0xC9, 0xC8, 0x45,
+            0x8E, 0xFF, 0xAC,
+        ],
+    ),
+    (
+        1,
+        true,
+        &[
+            0x58, 0xE5, 0xD5, 0x40, 0x53, 0x14, 0x5D, 0xC3, 0xA1, 0x5A, 0x68, 0xF8, 0xCE, 0x57,
+            0x92, 0x91, 0xDE, 0xA2, 0x12, 0x06, 0x4F, 0x11, 0x06, 0x3A, 0xCC, 0xD4, 0x76, 0xFB,
+            0xFA, 0xB0, 0x5C, 0x74, 0xDA, 0x31, 0xCB, 0xFF, 0xAC,
+        ],
+    ),
+    (
+        2,
+        false,
+        &[
+            0x00, 0xEB, 0x5B, 0x40, 0x45, 0x35, 0xDC, 0x21, 0xC9, 0xCD, 0x1A, 0x34, 0x60, 0xBE,
+            0x94, 0x6E, 0x94, 0xC2, 0x2D, 0xE4, 0xB8, 0x36, 0x16, 0x07, 0x91, 0x74, 0xAF, 0xA0,
+            0xE9, 0xC3, 0xD9, 0x54, 0x7D, 0xEE, 0x43, 0xA1, 0xAB, 0xF9, 0x7E, 0x97, 0xE9, 0x09,
+            0x87, 0x7F, 0xFF, 0xAC,
+        ],
+    ),
+    (
+        2,
+        true,
+        &[
+            0x56, 0x76, 0xAD, 0xA0, 0xF2, 0x9E, 0xEE, 0x15, 0xCA, 0xB3, 0x46, 0x90, 0x18, 0x4F,
+            0xA5, 0x23, 0xBD, 0x2D, 0x16, 0xDD, 0x94, 0xE8, 0x2A, 0x95, 0xDD, 0x3E, 0x9D, 0x9A,
+            0x3F, 0x83, 0xA9, 0x94, 0xF7, 0x63, 0xD4, 0x7F, 0xFF, 0xAC,
+        ],
+    ),
+    (
+        3,
+        false,
+        &[
+            0x05, 0x60, 0xE3, 0x12, 0x8D, 0xF4, 0xB9, 0xE1, 0x16, 0x5A, 0xED, 0x65, 0x7D, 0x5C,
+            0x34, 0xFB, 0x9E, 0x31, 0xF1, 0xED, 0x17, 0x2A, 0x59, 0x87, 0xBA, 0x82, 0xC5, 0x0F,
+            0xF8, 0xC4, 0x0C, 0x7F, 0x05, 0x27, 0x5A, 0xCA, 0xC1, 0x20, 0xD8, 0x56, 0x48, 0x26,
+            0xD0, 0x7F, 0xFF, 0xAC,
+        ],
+    ),
+    (
+        3,
+        true,
+        &[
+            0x58, 0xB1, 0x71, 0x89, 0x60, 0x8E, 0x2E, 0x78, 0xA7, 0x4E, 0x5D, 0xAC, 0xE5, 0xF9,
+            0x43, 0x4D, 0xD6, 0x64, 0xB5, 0x76, 0x99, 0x31, 0x98, 0x19, 0x43, 0x0A, 0xE7, 0x5A,
+            0x6E, 0xC0, 0x20, 0xD8, 0x2A, 0xB6, 0x70, 0x8B, 0x7F, 0xFF, 0xAC,
+        ],
+    ),
+];
+
+fn reference_bitmap() -> BinaryImage {
+    binary_image![
+        "#...#.....###....##..#.....##",
+        "......#.#....###............#",
+        "..#..##...#.#.##.....##...#.#",
+        ".....#.......#...#.#.##.##...",
+        ".....#.......#...#.#.##.##...",
+        ".....#.......#...#.#.##.##...",
+        ".....#.......#...#.#.##.##...",
+        ".....#.......#...#.#.##.##...",
+        "..#.##...#..#...##.#..#..#...",
+        ".##.....#.##..........#.#....",
+        "..##....#..#.#..#.##.........",
+        ".#..##.#.#.#........#..#.....",
+        ".....#.#...........#..#.....#",
+    ]
+}
+
+#[test]
+fn test_mq_encoder() {
+    let mut encoder = MqEncoder::new();
+    let mut context = Context::default();
+    for byte in MQ_INPUT {
+        for bit in (0..8).rev() {
+            encoder.encode(&mut context, (byte >> bit) & 1);
+        }
+    }
+    assert_eq!(encoder.finish(), MQ_OUTPUT);
+}
+
+#[test]
+fn test_mq_decoder() {
+    let mut decoder = MqDecoder::new(&MQ_OUTPUT);
+    let mut context = Context::default();
+    for byte in MQ_INPUT {
+        let value = (0..8).fold(0, |value, _| (value << 1) | decoder.decode(&mut context));
+        assert_eq!(value, byte);
+    }
+}
+
+#[test]
+fn test_annex_h() {
+    let expected = annex_h_bitmap();
+    let params = GenericRegionParams::mmr();
+    let decoded = jbig2::decode_generic_region(&ANNEX_H_MMR, 54, 44, &params).unwrap();
+    assert_same(&decoded, &expected);
+
+    let params = GenericRegionParams {
+        typical_prediction: true,
+        adaptive_pixels: [(3, -1), (-3, -1), (2, -2), (-2, -2)],
+        ..GenericRegionParams::arithmetic(0)
+    };
+    let decoded = jbig2::decode_generic_region(&ANNEX_H_ARITHMETIC, 54, 44, &params).unwrap();
+    assert_same(&decoded, &expected);
+}
+
+#[test]
+fn test_reference_regions() {
+    let expected = reference_bitmap();
+    for (template, typical_prediction, data) in REFERENCE_REGIONS {
+        let params = GenericRegionParams {
+            typical_prediction,
+            ..GenericRegionParams::arithmetic(template)
+        };
+        let decoded = jbig2::decode_generic_region(data, 29, 13, &params).unwrap();
+        assert_same(&decoded, &expected);
+        assert_eq!(jbig2::encode_generic_region(&expected, &params), data);
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    // Noise with runs of identical rows for typical prediction.
+    let mut image = noise(67, 40, 9);
+    for y in 10..20 {
+        let row = image.crop(0, 9, 67, 1);
+        image.sub_view_mut(0, y, 67, 1).copy_from_image(&row);
+    }
+    image.sub_view_mut(0, 30, 67, 10).fill(Bit(true));
+
+    let mut params = vec![GenericRegionParams::mmr()];
+    for template in 0..4 {
+        for typical_prediction in [false, true] {
+            params.push(GenericRegionParams {
+                typical_prediction,
+                ..GenericRegionParams::arithmetic(template)
+            });
+        }
+    }
+    params.push(GenericRegionParams {
+        adaptive_pixels: [(-5, 0), (4, -2), (-128, -1), (0, -3)],
+        ..GenericRegionParams::arithmetic(0)
+    });
+    for params in &params {
+        let data = jbig2::encode_generic_region(&image, params);
+        let decoded = jbig2::decode_generic_region(&data, 67, 40, params).unwrap();
+        assert_same(&decoded, &image);
+    }
+
+    // Typical prediction codes identical rows with a single decision.
+    let blank = BinaryImage::new(500, 500);
+    let params = GenericRegionParams {
+        typical_prediction: true,
+        ..GenericRegionParams::arithmetic(0)
+    };
+    assert!(jbig2::encode_generic_region(&blank, &params).len() < 16);
+}
+
+#[test]
+fn test_invalid_params() {
+    let params = GenericRegionParams {
+        adaptive_pixels: [(1, 0), (-3, -1), (2, -2), (-2, -2)],
+        ..GenericRegionParams::arithmetic(1)
+    };
+    assert!(matches!(
+        jbig2::decode_generic_region(&[], 8, 8, &params),
+        Err(DecodingError::InvalidHeader(_))
+    ));
+    let params = GenericRegionParams {
+        template: 4,
+        ..params
+    };
+    assert!(matches!(
+        jbig2::decode_generic_region(&[], 8, 8, &params),
+        Err(DecodingError::InvalidHeader(_))
+    ));
+}
@@ -0,0 +1,65 @@
+use super::*;
+
+#[test]
+fn test_components() {
+    let image = BinaryImage::from_raw(4, 3, &[1u8, 0, 0, 1, 0, 1, 0, 1, 0, 0, 1, 1]);
+    let eight = image.components(Connectivity::Eight);
+    assert_eq!(eight.count(), 1);
+    let four = image.components(Connectivity::Four);
+    assert_eq!(four.count(), 3);
+    assert_eq!(four.labels(), &[1, 0, 0, 2, 0, 3, 0, 2, 0, 0, 2, 2]);
+    assert_eq!(four.areas(), [1, 4, 1]);
+    assert_eq!(four.bounding_boxes()[1].x, 2);
+    assert_same(
+        &four.mask(2),
+        &BinaryImage::from_raw(4, 3, &[0u8, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1]),
+    );
+}
+
+#[test]
+fn test_flood_fill() {
+    let image = noise(50, 40, 11);
+    for connectivity in [Connectivity::Four, Connectivity::Eight] {
+        let components = image.components(connectivity);
+        let neighbors: &[(i32, i32)] = match connectivity {
+            Connectivity::Four => &[(1, 0), (0, 1), (-1, 0), (0, -1)],
+            Connectivity::Eight => &[
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+            ],
+        };
+        // Every component found by flood filling from its first pixel has a
+        // single label, and labels increase in row-major order.
+        let mut seen = vec![false; 50 * 40];
+        let mut next = 1;
+        for (x, y, pixel) in image.pixels() {
+            if !*pixel || seen[(y * 50 + x) as usize] {
+                continue;
+            }
+            assert_eq!(components.label(x, y), next);
+            let mut stack = vec![(x, y)];
+            seen[(y * 50 + x) as usize] = true;
+            while let Some((x, y)) = stack.pop() {
+                assert_eq!(components.label(x, y), next);
+                for &(dx, dy) in neighbors {
+                    let (nx, ny) = (x.wrapping_add_signed(dx), y.wrapping_add_signed(dy));
+                    if image.in_bounds(nx, ny)
+                        && *image.get_pixel(nx, ny)
+                        && !seen[(ny * 50 + nx) as usize]
+                    {
+                        seen[(ny * 50 + nx) as usize] = true;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+            next += 1;
+        }
+        assert_eq!(components.count(), next - 1);
+    }
+}
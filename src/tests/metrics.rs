@@ -0,0 +1,74 @@
+use super::*;
+use crate::binary_image;
+
+#[test]
+fn test_overlap() {
+    let predicted = binary_image!["####....", "####....", "........",];
+    let truth = binary_image!["..####..", "..####..", "........",];
+    let metrics = predicted.evaluate(&truth, 0.0);
+    assert_eq!(
+        (
+            metrics.true_positives,
+            metrics.false_positives,
+            metrics.false_negatives,
+            metrics.true_negatives
+        ),
+        (4, 4, 4, 12)
+    );
+    assert!((metrics.iou - 1.0 / 3.0).abs() < 1e-12);
+    assert!((metrics.dice - 0.5).abs() < 1e-12);
+    assert!((metrics.precision - 0.5).abs() < 1e-12);
+    assert!((metrics.recall - 0.5).abs() < 1e-12);
+    assert!((metrics.accuracy - 16.0 / 24.0).abs() < 1e-12);
+    // All 8 pixels of each mask are on its boundary, and half of each lie on
+    // the other boundary.
+    assert!((metrics.boundary_precision - 0.5).abs() < 1e-12);
+    assert!((metrics.boundary_f1 - 0.5).abs() < 1e-12);
+    // Boundary distances are 2, 1, 0 and 0 along each row, on both sides.
+    assert!((metrics.assd - 0.75).abs() < 1e-12);
+
+    let tolerant = predicted.evaluate(&truth, 2.0);
+    assert!((tolerant.boundary_f1 - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_empty() {
+    let empty = BinaryImage::new(4, 4);
+    let metrics = empty.evaluate(&empty, 1.0);
+    assert_eq!(
+        (metrics.iou, metrics.dice, metrics.precision, metrics.recall),
+        (1.0, 1.0, 1.0, 1.0)
+    );
+    assert_eq!((metrics.boundary_f1, metrics.assd), (1.0, 0.0));
+
+    let mut image = BinaryImage::new(4, 4);
+    image.put_pixel(1, 2, Bit(true));
+    let metrics = empty.evaluate(&image, 1.0);
+    assert_eq!(
+        (metrics.iou, metrics.precision, metrics.recall),
+        (0.0, 0.0, 0.0)
+    );
+    assert!(metrics.boundary_f1.abs() < f64::EPSILON);
+    assert!(metrics.assd.is_infinite());
+}
+
+#[test]
+fn test_noise() {
+    let predicted = noise(29, 31, 1);
+    let truth = noise(29, 31, 2);
+    let metrics = predicted.evaluate(&truth, 1.0);
+    let mut counts = [0u64; 4];
+    for (x, y, pixel) in predicted.pixels() {
+        counts[usize::from(*pixel) * 2 + usize::from(*truth.get_pixel(x, y))] += 1;
+    }
+    assert_eq!(
+        [
+            metrics.true_negatives,
+            metrics.false_negatives,
+            metrics.false_positives,
+            metrics.true_positives
+        ],
+        counts
+    );
+    assert!((0.0..=1.0).contains(&metrics.boundary_f1));
+}
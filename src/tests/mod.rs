@@ -1,7 +1,35 @@
 use image::{ImageBuffer, Luma};
 
 use super::*;
+mod ascii;
+mod bmp;
+mod ccitt;
+mod coco;
+mod compare;
+mod contour;
+mod distance;
+mod geo;
+mod geometry;
+mod hull;
+mod jbig2;
+mod label;
+mod metrics;
+mod moments;
+mod packed;
+mod pbm;
 mod pixel;
+#[cfg(feature = "png")]
+mod png;
+mod region;
+mod render;
+mod rle;
+#[cfg(feature = "serde")]
+mod serde;
+mod svg;
+mod tiff;
+mod trace;
+mod view;
+mod xbm;
 
 static DATA: [u8; 16] = [
     1, 0, 0, 0, //
@@ -10,6 +38,27 @@ static DATA: [u8; 16] = [
     0, 0, 0, 1, //
 ];
 
+/// Deterministic pseudo-random image for comparing fast paths against
+/// per-pixel reference implementations.
+fn noise(width: u32, height: u32, mut seed: u32) -> BinaryImage {
+    let data: Vec<u8> = (0..width * height)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            u8::from(seed.trailing_zeros() >= 2)
+        })
+        .collect();
+    BinaryImage::from_raw(width, height, &data)
+}
+
+fn assert_same<A: GenericImageView<Pixel = Bit>, B: GenericImageView<Pixel = Bit>>(a: &A, b: &B) {
+    assert_eq!(a.dimensions(), b.dimensions());
+    for (x, y, pixel) in a.pixels() {
+        assert_eq!(pixel, b.get_pixel(x, y), "Mismatch at ({x}, {y})");
+    }
+}
+
 fn test_view<I: GenericImageView<Pixel = Bit>>(image: &I) {
     for ((_, _, pixel1), pixel2) in image.pixels().zip(DATA) {
         assert!(!(*pixel1 ^ (pixel2 > 0)));
@@ -0,0 +1,103 @@
+#![allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+
+use std::f64::consts::FRAC_PI_2;
+
+use super::*;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0)
+}
+
+#[test]
+fn test_moments() {
+    let image = noise(41, 23, 9);
+    let moments = image.moments();
+    let set: Vec<_> = image
+        .pixels()
+        .filter(|(_, _, p)| **p)
+        .map(|(x, y, _)| (f64::from(x), f64::from(y)))
+        .collect();
+    let area = set.len() as f64;
+    let (cx, cy) = set.iter().fold((0.0, 0.0), |(sx, sy), &(x, y)| {
+        (sx + x / area, sy + y / area)
+    });
+    let (mx, my) = moments.centroid().unwrap();
+    assert!(close(mx, cx) && close(my, cy));
+    for p in 0..4usize {
+        for q in 0..4 - p {
+            let spatial: f64 = set
+                .iter()
+                .map(|&(x, y)| x.powi(p as i32) * y.powi(q as i32))
+                .sum();
+            let central: f64 = set
+                .iter()
+                .map(|&(x, y)| (x - cx).powi(p as i32) * (y - cy).powi(q as i32))
+                .sum();
+            assert!(close(moments.spatial[p][q], spatial), "m{p}{q}");
+            assert!(close(moments.central[p][q], central), "mu{p}{q}");
+        }
+    }
+    assert!(close(moments.normalized[0][0], 1.0));
+    assert_eq!(BinaryImage::new(5, 5).moments(), Moments::default());
+}
+
+#[test]
+fn test_hu_invariance() {
+    let mut image = BinaryImage::new(40, 40);
+    image.sub_view_mut(5, 5, 20, 6).fill(Bit(true));
+    image.sub_view_mut(5, 11, 4, 15).fill(Bit(true));
+    let hu = image.moments().hu();
+
+    let rotated = image.rotated90().materialize();
+    let moved = image.crop(0, 0, 30, 30).pad(7, 3, 0, 0, Bit(false));
+    for other in [rotated.moments().hu(), moved.moments().hu()] {
+        for (a, b) in hu.iter().zip(other) {
+            assert!(close(*a, b), "{hu:?} != {other:?}");
+        }
+    }
+    let mirrored = image.flip_horizontal().moments().hu();
+    assert!(close(hu[6], -mirrored[6]));
+}
+
+#[test]
+fn test_ellipse() {
+    let mut image = BinaryImage::new(30, 30);
+    image.sub_view_mut(2, 10, 21, 3).fill(Bit(true));
+    let moments = image.moments();
+    assert!(close(moments.orientation(), 0.0));
+    let (major, minor) = moments.ellipse_axes();
+    // A uniform run of n pixels has variance (n² - 1) / 12.
+    assert!(close(major, 4.0 * (440.0_f64 / 12.0).sqrt()));
+    assert!(close(minor, 4.0 * (8.0_f64 / 12.0).sqrt()));
+    assert!(moments.eccentricity() > 0.9);
+
+    let vertical = image.transposed().materialize().moments();
+    assert!(close(vertical.orientation().abs(), FRAC_PI_2));
+
+    let mut diagonal = BinaryImage::new(10, 10);
+    for i in 0..10 {
+        diagonal.put_pixel(i, i, Bit(true));
+    }
+    let moments = diagonal.moments();
+    assert!(close(moments.orientation(), FRAC_PI_2 / 2.0));
+    assert!(close(moments.eccentricity(), 1.0));
+
+    let mut square = BinaryImage::new(8, 8);
+    square.sub_view_mut(2, 2, 4, 4).fill(Bit(true));
+    assert!(square.moments().eccentricity().abs() < 1e-9);
+}
+
+#[test]
+fn test_components() {
+    let image = noise(37, 19, 4);
+    let components = image.components(Connectivity::Eight);
+    let moments = components.moments();
+    assert_eq!(moments.len(), components.count() as usize);
+    for (label, moments) in (1..).zip(&moments) {
+        assert_eq!(*moments, components.mask(label).moments());
+    }
+}
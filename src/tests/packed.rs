@@ -0,0 +1,70 @@
+use super::*;
+use crate::codecs::DecodingError;
+
+#[test]
+fn test_bit_order() {
+    // 10 pixels per row: 1000000001 and 0110000000.
+    let mut image = BinaryImage::new(10, 2);
+    image.put_pixel(0, 0, Bit(true));
+    image.put_pixel(9, 0, Bit(true));
+    image.put_pixel(1, 1, Bit(true));
+    image.put_pixel(2, 1, Bit(true));
+
+    let msb = PackedLayout::default();
+    assert_eq!(image.to_packed(&msb), [0x80, 0x40, 0x60, 0x00]);
+    let lsb = PackedLayout {
+        bit_order: BitOrder::LsbFirst,
+        ..PackedLayout::default()
+    };
+    assert_eq!(image.to_packed(&lsb), [0x01, 0x02, 0x06, 0x00]);
+    let inverted = PackedLayout {
+        invert: true,
+        ..PackedLayout::default()
+    };
+    assert_eq!(image.to_packed(&inverted), [0x7F, 0x80, 0x9F, 0xC0]);
+
+    for layout in [msb, lsb, inverted] {
+        let bytes = image.to_packed(&layout);
+        assert_same(
+            &BinaryImage::from_packed(10, 2, &bytes, &layout).unwrap(),
+            &image,
+        );
+    }
+}
+
+#[test]
+fn test_stride() {
+    let image = noise(21, 5, 4);
+    let aligned = PackedLayout {
+        row_alignment: 4,
+        ..PackedLayout::default()
+    };
+    assert_eq!(aligned.stride(21), 4);
+    assert_eq!(aligned.min_len(21, 5), 19);
+    let bytes = image.to_packed(&aligned);
+    assert_eq!(bytes.len(), 20);
+    assert_same(
+        &BinaryImage::from_packed(21, 5, &bytes[..19], &aligned).unwrap(),
+        &image,
+    );
+
+    // Padding bytes of an existing buffer are kept.
+    let strided = PackedLayout {
+        row_stride: Some(5),
+        bit_order: BitOrder::LsbFirst,
+        ..PackedLayout::default()
+    };
+    let mut framebuffer = vec![0xAA; 25];
+    image.write_packed(&mut framebuffer, &strided);
+    assert_eq!(&framebuffer[3..5], &[0xAA, 0xAA]);
+    assert_same(
+        &BinaryImage::from_packed(21, 5, &framebuffer, &strided).unwrap(),
+        &image,
+    );
+
+    assert!(matches!(
+        BinaryImage::from_packed(21, 5, &bytes[..18], &aligned),
+        Err(DecodingError::UnexpectedEof)
+    ));
+    assert!(BinaryImage::from_packed(0, 3, &[], &aligned).is_ok());
+}
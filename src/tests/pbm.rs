@@ -0,0 +1,106 @@
+use super::*;
+use crate::codecs::pbm::{self, PbmDecoder, PbmFormat};
+use crate::codecs::DecodingError;
+
+#[test]
+fn test_decode_plain() {
+    let data = b"P1\n# a comment\n4 4\n0111\n1 0 0 1 # trailing\n10\n11\n1 1 1 0\n";
+    let image = pbm::decode(&data[..]).unwrap();
+
+    assert_same(&image, &BinaryImage::from_raw(4, 4, &DATA));
+}
+
+#[test]
+fn test_decode_raw() {
+    let data = b"P4 4#comment\n4\n\x70\x90\xB0\xE0";
+    let image = pbm::decode(&data[..]).unwrap();
+
+    assert_same(&image, &BinaryImage::from_raw(4, 4, &DATA));
+}
+
+#[test]
+fn test_round_trip() {
+    let image = noise(83, 19, 1);
+    for format in [PbmFormat::Ascii, PbmFormat::Raw] {
+        let mut data = Vec::new();
+        image.write_pbm(&mut data, format).unwrap();
+        assert_same(&pbm::decode(data.as_slice()).unwrap(), &image);
+    }
+
+    let mut data = Vec::new();
+    image.write_pbm(&mut data, PbmFormat::Ascii).unwrap();
+    assert!(data
+        .split(|&byte| byte == b'\n')
+        .all(|line| line.len() <= 70));
+}
+
+#[test]
+fn test_encode_raw_padding() {
+    let mut data = Vec::new();
+    BinaryImage::new(3, 1)
+        .write_pbm(&mut data, PbmFormat::Raw)
+        .unwrap();
+    assert_eq!(data, b"P4\n3 1\n\xE0");
+}
+
+#[test]
+fn test_multi_image_stream() {
+    let mut data = Vec::new();
+    let images = [noise(9, 4, 2), noise(40, 3, 3), BinaryImage::new(1, 1)];
+    for image in &images {
+        image.write_pbm(&mut data, PbmFormat::Raw).unwrap();
+    }
+    data.extend_from_slice(b"\n\n");
+
+    let decoded: Vec<_> = PbmDecoder::new(data.as_slice())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(decoded.len(), images.len());
+    for (decoded, image) in decoded.iter().zip(&images) {
+        assert_same(decoded, image);
+    }
+}
+
+#[test]
+fn test_malformed() {
+    let error = |data: &[u8]| pbm::decode(data).unwrap_err();
+
+    assert!(matches!(error(b""), DecodingError::UnexpectedEof));
+    assert!(matches!(error(b"P4\n4"), DecodingError::UnexpectedEof));
+    assert!(matches!(
+        error(b"P4\n4 4\n\x70\x90"),
+        DecodingError::UnexpectedEof
+    ));
+    assert!(matches!(
+        error(b"P1 2 2 0 1 1"),
+        DecodingError::UnexpectedEof
+    ));
+    // Huge headers fail on the missing raster instead of allocating for it.
+    assert!(matches!(
+        error(b"P4 65535 65535\n"),
+        DecodingError::UnexpectedEof
+    ));
+    assert!(matches!(
+        error(b"P1 65535 65535 0 1"),
+        DecodingError::UnexpectedEof
+    ));
+    assert!(matches!(
+        error(b"P1 2 2 0 1 2 1"),
+        DecodingError::InvalidData(_)
+    ));
+    assert!(matches!(
+        error(b"P4 x 4\n"),
+        DecodingError::InvalidHeader(_)
+    ));
+    assert!(matches!(error(b"P4 4 4x"), DecodingError::InvalidHeader(_)));
+    assert!(matches!(error(b"GIF89a"), DecodingError::InvalidHeader(_)));
+    assert!(matches!(
+        error(b"P5 4 4 255\n"),
+        DecodingError::Unsupported(_)
+    ));
+
+    let mut decoder = PbmDecoder::new(&b"P1 1 1 0 garbage"[..]);
+    assert!(decoder.next().unwrap().is_ok());
+    assert!(decoder.next().unwrap().is_err());
+    assert!(decoder.next().is_none());
+}
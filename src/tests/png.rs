@@ -0,0 +1,80 @@
+use std::io::Cursor;
+
+use super::*;
+use crate::codecs::{png, DecodingError};
+
+#[test]
+fn test_round_trip() {
+    for (width, height) in [(1, 1), (8, 2), (33, 17)] {
+        let image = noise(width, height, 5 + width);
+        let mut data = Vec::new();
+        image.write_png(&mut data).unwrap();
+        assert_same(&png::decode(Cursor::new(data)).unwrap(), &image);
+    }
+}
+
+#[test]
+fn test_encode_packed() {
+    let mut data = Vec::new();
+    BinaryImage::from_raw(4, 4, &DATA)
+        .write_png(&mut data)
+        .unwrap();
+
+    let decoder = ::png::Decoder::new(Cursor::new(data));
+    let mut reader = decoder.read_info().unwrap();
+    let mut buffer = vec![0; reader.output_buffer_size().unwrap()];
+    reader.next_frame(&mut buffer).unwrap();
+    assert_eq!(reader.info().bit_depth, ::png::BitDepth::One);
+    assert_eq!(buffer, [0x80, 0x60, 0x40, 0x10]);
+}
+
+fn encode_with(
+    color: ::png::ColorType,
+    depth: ::png::BitDepth,
+    palette: Option<Vec<u8>>,
+    rows: &[u8],
+) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut encoder = ::png::Encoder::new(&mut data, 4, 4);
+    encoder.set_color(color);
+    encoder.set_depth(depth);
+    if let Some(palette) = palette {
+        encoder.set_palette(palette);
+    }
+    encoder
+        .write_header()
+        .unwrap()
+        .write_image_data(rows)
+        .unwrap();
+    data
+}
+
+#[test]
+fn test_decode_indexed() {
+    let data = encode_with(
+        ::png::ColorType::Indexed,
+        ::png::BitDepth::One,
+        Some(vec![255, 0, 0, 0, 0, 0]),
+        &[0x80, 0x60, 0x40, 0x10],
+    );
+    let image = png::decode(Cursor::new(data)).unwrap();
+    assert_same(&image, &BinaryImage::from_raw(4, 4, &DATA).inverted());
+}
+
+#[test]
+fn test_unsupported() {
+    let data = encode_with(
+        ::png::ColorType::Grayscale,
+        ::png::BitDepth::Eight,
+        None,
+        &[0; 16],
+    );
+    assert!(matches!(
+        png::decode(Cursor::new(data)),
+        Err(DecodingError::Unsupported(_))
+    ));
+    assert!(matches!(
+        png::decode(Cursor::new(b"\x89PNG\r\n\x1a\n".to_vec())),
+        Err(DecodingError::UnexpectedEof)
+    ));
+}
@@ -0,0 +1,93 @@
+#![allow(clippy::cast_precision_loss)]
+
+use std::f64::consts::PI;
+
+use super::*;
+use crate::binary_image;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn test_region_props() {
+    let image = binary_image![
+        "............",
+        ".#####......",
+        ".#...#..###.",
+        ".#...#..###.",
+        ".#####......",
+        "............",
+    ];
+    let regions = image.region_props(&RegionOptions {
+        perimeter: Perimeter::ChainCode,
+        ..RegionOptions::default()
+    });
+    assert_eq!(regions.len(), 2);
+
+    let ring = &regions[0];
+    assert_eq!((ring.label, ring.area, ring.euler_number), (1, 14, 0));
+    assert_eq!((ring.bounding_box.x, ring.bounding_box.y), (1, 1));
+    assert!(close(ring.perimeter, 14.0));
+    assert!(close(ring.convex_area, 20.0));
+    assert!(close(ring.solidity, 0.7));
+    assert!(close(ring.extent, 0.7));
+    assert!(close(ring.feret_max, 41f64.sqrt()));
+    assert!(close(ring.feret_min, 4.0));
+    assert_eq!(ring.moments.centroid(), Some((3.0, 2.5)));
+
+    let block = &regions[1];
+    assert_eq!((block.area, block.euler_number), (6, 1));
+    assert!(close(block.perimeter, 6.0));
+    assert!(close(block.solidity, 1.0) && close(block.extent, 1.0));
+    assert!(close(block.feret_max, 13f64.sqrt()) && close(block.feret_min, 2.0));
+
+    let dot = BinaryImage::from_raw(1, 1, &[1u8]).region_props(&RegionOptions::default());
+    assert!(close(dot[0].convex_area, 1.0) && close(dot[0].feret_max, 2f64.sqrt()));
+}
+
+#[test]
+fn test_perimeter() {
+    let radius = 40.0;
+    let mut disk = BinaryImage::new(100, 100);
+    for y in 0..100 {
+        for x in 0..100 {
+            let inside = (f64::from(x) - 50.0).hypot(f64::from(y) - 50.0) < radius;
+            disk.put_pixel(x, y, Bit(inside));
+        }
+    }
+    let crofton = &disk.region_props(&RegionOptions::default())[0];
+    let chain = &disk.region_props(&RegionOptions {
+        perimeter: Perimeter::ChainCode,
+        ..RegionOptions::default()
+    })[0];
+    for perimeter in [crofton.perimeter, chain.perimeter] {
+        assert!(
+            (perimeter / (2.0 * PI * radius) - 1.0).abs() < 0.05,
+            "{perimeter}"
+        );
+    }
+    assert!((crofton.circularity - 1.0).abs() < 0.05);
+    assert!(crofton.solidity > 0.95 && crofton.solidity < 1.0);
+}
+
+#[test]
+fn test_euler_number() {
+    let image = noise(37, 29, 5);
+    // Holes are the components of the padded background, save the outer one.
+    let background = image.pad(1, 1, 1, 1, Bit(false));
+    let mut inverted = BinaryImage::new(background.width(), background.height());
+    for (x, y, pixel) in background.pixels() {
+        inverted.put_pixel(x, y, Bit(!*pixel));
+    }
+    for (connectivity, opposite) in [
+        (Connectivity::Four, Connectivity::Eight),
+        (Connectivity::Eight, Connectivity::Four),
+    ] {
+        let holes = i64::from(inverted.components(opposite).count()) - 1;
+        assert_eq!(
+            image.euler_number(connectivity),
+            i64::from(image.components(connectivity).count()) - holes
+        );
+    }
+}
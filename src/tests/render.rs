@@ -0,0 +1,60 @@
+use super::*;
+
+fn checker() -> BinaryImage {
+    // Set pixels where x + y is even, in a 4x4 image.
+    BinaryImage::from_raw(
+        4,
+        4,
+        &[
+            1u8, 0, 1, 0, //
+            0, 1, 0, 1, //
+            1, 0, 1, 0, //
+            0, 1, 0, 1, //
+        ],
+    )
+}
+
+#[test]
+fn test_glyphs() {
+    let image = BinaryImage::from_raw(3, 3, &[1u8, 0, 1, 1, 1, 0, 0, 0, 1]);
+    assert_eq!(image.to_string(), "█▄▀\n  ▀");
+
+    let quadrant = TextOptions {
+        glyphs: Glyphs::Quadrant,
+        ..TextOptions::default()
+    };
+    assert_eq!(image.render(&quadrant).to_string(), "▙▘\n ▘");
+    assert_eq!(checker().render(&quadrant).to_string(), "▚▚\n▚▚");
+
+    let braille = TextOptions {
+        glyphs: Glyphs::Braille,
+        ..TextOptions::default()
+    };
+    assert_eq!(checker().render(&braille).to_string(), "⢕⢕");
+    let mut image = BinaryImage::new(2, 4);
+    image.sub_view_mut(0, 0, 2, 4).fill(Bit(true));
+    assert_eq!(image.render(&braille).to_string(), "⣿");
+    assert_eq!(BinaryImage::new(0, 0).to_string(), "");
+}
+
+#[test]
+fn test_options() {
+    let image = checker();
+    let colored = image.render(&TextOptions {
+        colors: Some(AnsiColors::default()),
+        ..TextOptions::default()
+    });
+    assert_eq!(
+        colored.to_string(),
+        "\x1b[38;5;15;48;5;0m▀▄▀▄\x1b[0m\n\x1b[38;5;15;48;5;0m▀▄▀▄\x1b[0m"
+    );
+
+    let mut image = BinaryImage::new(20, 4);
+    image.put_pixel(19, 3, Bit(true));
+    let shrunk = image.render(&TextOptions {
+        max_width: Some(8),
+        ..TextOptions::default()
+    });
+    // Shrunk by 3 into 7 columns.
+    assert_eq!(shrunk.to_string(), "      ▄");
+}
@@ -0,0 +1,89 @@
+use super::*;
+use crate::codecs::DecodingError;
+
+#[test]
+fn test_conversion() {
+    for (width, height) in [(1, 1), (31, 4), (32, 3), (100, 17)] {
+        let image = noise(width, height, width);
+        let rle = RleImage::from(&image);
+        assert_same(&rle, &image);
+        assert_same(&BinaryImage::from(&rle), &image);
+    }
+
+    let mut full = BinaryImage::new(70, 2);
+    full.sub_view_mut(0, 1, 70, 1).fill(Bit(true));
+    let rle = RleImage::from(&full);
+    assert_eq!(rle.row(0), &[]);
+    assert_eq!(rle.row(1), &[(0, 70)]);
+}
+
+#[test]
+fn test_from_rows() {
+    let rle = RleImage::from_rows(10, 3, [vec![(5, 7), (0, 2), (6, 8), (8, 9)], vec![(3, 3)]]);
+    assert_eq!(rle.row(0), &[(0, 2), (5, 9)]);
+    assert_eq!(rle.row(1), &[]);
+    assert_eq!(rle.row(2), &[]);
+    assert_eq!(rle.run_count(), 2);
+
+    // Empty images compare equal however they are built.
+    let empty = RleImage::from(&BinaryImage::new(0, 0));
+    assert_eq!(RleImage::default(), RleImage::new(0, 0));
+    assert_eq!(RleImage::default(), empty);
+}
+
+#[test]
+fn test_ops() {
+    let (a, b) = (noise(75, 13, 1), noise(75, 13, 2));
+    let (rle_a, rle_b) = (RleImage::from(&a), RleImage::from(&b));
+
+    assert_same(&rle_a.and(&rle_b), &a.transformed().and(b.transformed()));
+    assert_same(&rle_a.or(&rle_b), &a.transformed().or(b.transformed()));
+    assert_same(&rle_a.xor(&rle_b), &a.transformed().xor(b.transformed()));
+    assert_eq!(rle_a.xor(&rle_a), RleImage::new(75, 13));
+    assert_eq!(rle_a.or(&rle_a), rle_a);
+
+    let count = a.pixels().filter(|(_, _, pixel)| **pixel).count();
+    assert_eq!(rle_a.area(), count as u64);
+    assert_eq!(rle_a.bounding_box(), a.bounding_box());
+    assert_eq!(RleImage::new(5, 5).bounding_box(), None);
+}
+
+#[test]
+fn test_dilate_rows() {
+    let image = noise(60, 9, 3);
+    let dilated = RleImage::from(&image).dilate_rows(2);
+    for (x, y, pixel) in dilated.pixels() {
+        let expected = (x.saturating_sub(2)..(x + 3).min(60)).any(|x| *image.get_pixel(x, y));
+        assert_eq!(*pixel, expected, "Mismatch at ({x}, {y})");
+    }
+}
+
+#[test]
+fn test_serialization() {
+    let rle = RleImage::from(&noise(300, 20, 4));
+    let bytes = rle.to_bytes();
+    assert_eq!(RleImage::from_bytes(&bytes).unwrap(), rle);
+
+    let rle = RleImage::from_rows(200, 2, [vec![(130, 140)], vec![]]);
+    assert_eq!(rle.to_bytes(), [200, 1, 2, 1, 130, 1, 10, 0]);
+
+    let error = |bytes: &[u8]| RleImage::from_bytes(bytes).unwrap_err();
+    assert!(matches!(error(&[4, 1, 1]), DecodingError::UnexpectedEof));
+    // A height of `u32::MAX` in six bytes.
+    assert!(matches!(
+        error(&[1, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+        DecodingError::UnexpectedEof
+    ));
+    assert!(matches!(
+        error(&[4, 1, 1, 2, 3]),
+        DecodingError::InvalidData(_)
+    ));
+    assert!(matches!(
+        error(&[4, 1, 2, 0, 1, 0, 1]),
+        DecodingError::InvalidData(_)
+    ));
+    assert!(matches!(
+        error(&[4, 1, 0, 0]),
+        DecodingError::InvalidData(_)
+    ));
+}
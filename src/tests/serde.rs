@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn test_json() {
+    let image = BinaryImage::from_raw(3, 3, &[1u8, 0, 0, 0, 1, 0, 0, 0, 1]);
+    let json = serde_json::to_string(&image).unwrap();
+    assert_eq!(json, r#"{"width":3,"height":3,"data":"iIA="}"#);
+    let decoded: BinaryImage = serde_json::from_str(&json).unwrap();
+    assert_same(&decoded, &image);
+
+    assert_eq!(serde_json::to_string(&Bit(true)).unwrap(), "true");
+    assert_eq!(serde_json::from_str::<Bit>("false").unwrap(), Bit(false));
+}
+
+#[test]
+fn test_binary() {
+    let image = noise(37, 11, 8);
+    let bytes = bincode::serialize(&image).unwrap();
+    // Two dimensions, the length prefix and the packed pixels.
+    assert_eq!(bytes.len(), 4 + 4 + 8 + (37 * 11usize).div_ceil(8));
+    let decoded: BinaryImage = bincode::deserialize(&bytes).unwrap();
+    assert_same(&decoded, &image);
+}
+
+#[test]
+fn test_validation() {
+    let short = r#"{"width":3,"height":3,"data":"iA=="}"#;
+    assert!(serde_json::from_str::<BinaryImage>(short).is_err());
+    let bad = r#"{"width":3,"height":3,"data":"iIA"}"#;
+    assert!(serde_json::from_str::<BinaryImage>(bad).is_err());
+    let huge = r#"{"width":100000,"height":100000,"data":""}"#;
+    assert!(serde_json::from_str::<BinaryImage>(huge).is_err());
+}
@@ -0,0 +1,64 @@
+use super::*;
+use crate::codecs::svg::{PathStyle, SvgOptions};
+
+fn path_data(svg: &str) -> Vec<&str> {
+    svg.split(" d=\"")
+        .skip(1)
+        .map(|rest| &rest[..rest.find('"').unwrap()])
+        .collect()
+}
+
+#[test]
+fn test_styles() {
+    let mut image = BinaryImage::new(4, 3);
+    image.sub_view_mut(1, 1, 2, 2).fill(Bit(true));
+
+    let staircase = image.to_svg(&SvgOptions {
+        style: PathStyle::Staircase,
+        ..SvgOptions::default()
+    });
+    assert!(staircase.starts_with(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="3" viewBox="0 0 4 3">"#
+    ));
+    assert!(staircase.contains(r#"fill="black" fill-rule="evenodd""#));
+    assert_eq!(path_data(&staircase), ["M1 1H3V3H1Z"]);
+
+    let polygon = image.to_svg(&SvgOptions::default());
+    assert_eq!(
+        path_data(&polygon),
+        ["M1.5 1L2.5 1L3 1.5L3 2.5L2.5 3L1.5 3L1 2.5L1 1.5Z"]
+    );
+
+    let smooth = image.to_svg(&SvgOptions {
+        style: PathStyle::Smooth,
+        ..SvgOptions::default()
+    });
+    assert_eq!(
+        path_data(&smooth),
+        ["M1.5 1L2.5 1Q3 1 3 1.5L3 2.5Q3 3 2.5 3L1.5 3Q1 3 1 2.5L1 1.5Q1 1 1.5 1Z"]
+    );
+
+    assert_eq!(
+        path_data(&BinaryImage::new(2, 2).to_svg(&SvgOptions::default())),
+        [""; 0]
+    );
+}
+
+#[test]
+fn test_components() {
+    // A ring and a separate pixel.
+    let mut image = BinaryImage::new(6, 3);
+    image.sub_view_mut(0, 0, 3, 3).fill(Bit(true));
+    image.put_pixel(1, 1, Bit(false));
+    image.put_pixel(5, 2, Bit(true));
+
+    let svg = image.to_svg(&SvgOptions {
+        style: PathStyle::Staircase,
+        group_components: true,
+        id_prefix: "mask-".into(),
+        ..SvgOptions::default()
+    });
+    assert!(svg.contains(r#"<g id="mask-1">"#));
+    assert!(svg.contains(r#"<g id="mask-2">"#));
+    assert_eq!(path_data(&svg), ["M0 0H3V3H0Z M1 1V2H2V1Z", "M5 2H6V3H5Z"]);
+}
@@ -0,0 +1,237 @@
+use super::*;
+use crate::codecs::tiff::{self, Resolution, ResolutionUnit, TiffCompression, TiffPage};
+use crate::codecs::DecodingError;
+
+#[test]
+fn test_pack_bits() {
+    let bytes = [
+        0xAA, 0xAA, 0xAA, 0x80, 0x00, 0x2A, 0xAA, 0xAA, 0xAA, 0xAA, 0x80, 0x00, 0x2A, 0x22, 0xAA,
+        0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA,
+    ];
+    let mut packed = Vec::new();
+    tiff::pack_bits(&bytes, &mut packed);
+    assert_eq!(
+        packed,
+        [
+            0xFE, 0xAA, 0x02, 0x80, 0x00, 0x2A, 0xFD, 0xAA, 0x03, 0x80, 0x00, 0x2A, 0x22, 0xF7,
+            0xAA
+        ]
+    );
+
+    let mut unpacked = Vec::new();
+    tiff::unpack_bits(&packed, bytes.len(), &mut unpacked).unwrap();
+    assert_eq!(unpacked, bytes);
+}
+
+#[test]
+fn test_round_trip() {
+    let pages = [
+        TiffPage {
+            image: noise(83, 19, 1),
+            resolution: Some(Resolution {
+                x: 300.0,
+                y: 150.0,
+                unit: ResolutionUnit::Inch,
+            }),
+        },
+        TiffPage {
+            image: noise(300, 7, 2),
+            resolution: Some(Resolution {
+                x: 118.11,
+                y: 118.11,
+                unit: ResolutionUnit::Centimeter,
+            }),
+        },
+        TiffPage {
+            image: BinaryImage::new(1, 1),
+            resolution: None,
+        },
+    ];
+    for compression in [
+        TiffCompression::None,
+        TiffCompression::PackBits,
+        TiffCompression::CcittRle,
+        TiffCompression::Group3 {
+            two_dimensional: false,
+        },
+        TiffCompression::Group3 {
+            two_dimensional: true,
+        },
+        TiffCompression::Group4,
+    ] {
+        let mut data = Vec::new();
+        tiff::encode(&pages, &mut data, compression).unwrap();
+        let decoded = tiff::decode(data.as_slice()).unwrap();
+        assert_eq!(decoded.len(), pages.len());
+        for (decoded, page) in decoded.iter().zip(&pages) {
+            assert_same(&decoded.image, &page.image);
+            assert_eq!(decoded.resolution, page.resolution);
+        }
+    }
+}
+
+/// A big-endian `BlackIsZero` file in two strips with reversed fill order.
+#[test]
+fn test_decode_variants() {
+    let image = BinaryImage::from_raw(4, 4, &DATA);
+    // Rows as `BlackIsZero` samples, least significant bit first.
+    let strips = [0x01, 0x06, 0x02, 0x08];
+    let mut data = b"MM\0*\0\0\0\x0C".to_vec();
+    data.extend_from_slice(&strips);
+    let entries: [(u16, u16, u32, u32); 10] = [
+        (256, 3, 1, 4 << 16),
+        (257, 3, 1, 4 << 16),
+        (258, 3, 1, 1 << 16),
+        (259, 3, 1, 1 << 16),
+        (262, 3, 1, 1 << 16),
+        (266, 3, 1, 2 << 16),
+        (273, 3, 2, 0x0008_000A),
+        (277, 3, 1, 1 << 16),
+        (278, 4, 1, 2),
+        (279, 3, 2, 0x0002_0002),
+    ];
+    data.extend_from_slice(&10u16.to_be_bytes());
+    for (tag, kind, count, value) in entries {
+        data.extend_from_slice(&tag.to_be_bytes());
+        data.extend_from_slice(&kind.to_be_bytes());
+        data.extend_from_slice(&count.to_be_bytes());
+        data.extend_from_slice(&value.to_be_bytes());
+    }
+    data.extend_from_slice(&[0; 4]);
+
+    let pages = tiff::decode(data.as_slice()).unwrap();
+    assert_eq!(pages.len(), 1);
+    assert_same(&pages[0].image, &image);
+    assert_eq!(pages[0].resolution, None);
+}
+
+/// `libtiff_bitmap()` as `WhiteIsZero` strips, written by libtiff 4.7 with
+/// each compression: uncompressed and `PackBits` in four-row strips with
+/// 16-bit byte counts, Group 4 in a single strip.
+const LIBTIFF_NONE: [u8; 198] = [
+    0x49, 0x49, 0x2A, 0x00, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7F, 0xFF, 0xFF,
+    0xFF, 0xFE, 0x49, 0x1D, 0x54, 0x3E, 0x0E, 0x49, 0x1D, 0x54, 0x3E, 0x0E, 0x7F, 0xFF, 0xFF, 0xFF,
+    0xFE, 0x70, 0x00, 0x00, 0x07, 0xF2, 0x55, 0x55, 0x55, 0x55, 0x56, 0x7F, 0xFF, 0xFF, 0xFF, 0xFE,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00,
+    0x28, 0x00, 0x00, 0x00, 0x01, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00,
+    0x02, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x01, 0x03, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x11, 0x01, 0x04, 0x00, 0x03, 0x00, 0x00, 0x00, 0xBA, 0x00, 0x00, 0x00,
+    0x15, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x16, 0x01, 0x03, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x17, 0x01, 0x03, 0x00, 0x03, 0x00, 0x00, 0x00,
+    0xB4, 0x00, 0x00, 0x00, 0x1C, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x14, 0x00, 0x14, 0x00, 0x05, 0x00, 0x08, 0x00, 0x00, 0x00, 0x1C, 0x00,
+    0x00, 0x00, 0x30, 0x00, 0x00, 0x00,
+];
+const LIBTIFF_PACK_BITS: [u8; 202] = [
+    0x49, 0x49, 0x2A, 0x00, 0x34, 0x00, 0x00, 0x00, 0xFC, 0x00, 0x00, 0x7F, 0xFE, 0xFF, 0x00, 0xFE,
+    0x04, 0x49, 0x1D, 0x54, 0x3E, 0x0E, 0x04, 0x49, 0x1D, 0x54, 0x3E, 0x0E, 0x00, 0x7F, 0xFE, 0xFF,
+    0x00, 0xFE, 0x04, 0x70, 0x00, 0x00, 0x07, 0xF2, 0xFD, 0x55, 0x00, 0x56, 0x00, 0x7F, 0xFE, 0xFF,
+    0x00, 0xFE, 0xFC, 0x00, 0x0A, 0x00, 0x00, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x28, 0x00,
+    0x00, 0x00, 0x01, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x02, 0x01,
+    0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x01, 0x03, 0x00, 0x01, 0x00,
+    0x00, 0x00, 0x05, 0x80, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x11, 0x01, 0x04, 0x00, 0x03, 0x00, 0x00, 0x00, 0xBE, 0x00, 0x00, 0x00, 0x15, 0x01,
+    0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x16, 0x01, 0x03, 0x00, 0x01, 0x00,
+    0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x17, 0x01, 0x04, 0x00, 0x03, 0x00, 0x00, 0x00, 0xB2, 0x00,
+    0x00, 0x00, 0x1C, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x16, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x08, 0x00,
+    0x00, 0x00, 0x1C, 0x00, 0x00, 0x00, 0x32, 0x00, 0x00, 0x00,
+];
+const LIBTIFF_GROUP4: [u8; 200] = [
+    0x49, 0x49, 0x2A, 0x00, 0x4A, 0x00, 0x00, 0x00, 0x91, 0xC3, 0x5B, 0x29, 0xCA, 0x72, 0xA0, 0xC3,
+    0x94, 0x39, 0x43, 0x94, 0x39, 0x56, 0x4F, 0x3F, 0xFF, 0xFF, 0xC4, 0x44, 0x44, 0x44, 0x47, 0x99,
+    0x58, 0x20, 0xB8, 0x44, 0x74, 0x47, 0x44, 0x74, 0x47, 0x44, 0x74, 0x47, 0x44, 0x74, 0x47, 0x44,
+    0x74, 0x47, 0x44, 0x74, 0x47, 0x41, 0x14, 0x39, 0x43, 0x94, 0x38, 0x40, 0xAB, 0x88, 0x88, 0x88,
+    0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0xE3, 0x00, 0x10, 0x01, 0x0A, 0x00, 0x00, 0x01, 0x03, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0x28, 0x00, 0x00, 0x00, 0x01, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00,
+    0x09, 0x00, 0x00, 0x00, 0x02, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+    0x03, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x01, 0x04, 0x00, 0x01, 0x00, 0x00, 0x00,
+    0x08, 0x00, 0x00, 0x00, 0x15, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+    0x16, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x17, 0x01, 0x04, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0x42, 0x00, 0x00, 0x00, 0x1C, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+fn libtiff_bitmap() -> BinaryImage {
+    binary_image![
+        "########################################",
+        "#......................................#",
+        "#.##.##.###...#.#.#.#.####.....#####...#",
+        "#.##.##.###...#.#.#.#.####.....#####...#",
+        "#......................................#",
+        "#...#########################.......##.#",
+        "#.#.#.#.#.#.#.#.#.#.#.#.#.#.#.#.#.#.#..#",
+        "#......................................#",
+        "########################################",
+    ]
+}
+
+#[test]
+fn test_libtiff() {
+    for data in [&LIBTIFF_NONE[..], &LIBTIFF_PACK_BITS, &LIBTIFF_GROUP4] {
+        let pages = tiff::decode(data).unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_same(&pages[0].image, &libtiff_bitmap());
+    }
+}
+
+#[test]
+fn test_malformed() {
+    let mut data = Vec::new();
+    noise(16, 16, 3)
+        .write_tiff(&mut data, TiffCompression::Group4)
+        .unwrap();
+
+    assert!(matches!(
+        tiff::decode(&b"GIF89a"[..]),
+        Err(DecodingError::InvalidHeader(_))
+    ));
+    assert!(matches!(
+        tiff::decode(&b"II+\0\x08\0\0\0"[..]),
+        Err(DecodingError::Unsupported(_))
+    ));
+    assert!(matches!(
+        tiff::decode(&data[..data.len() - 10]),
+        Err(DecodingError::UnexpectedEof)
+    ));
+
+    // Strip byte counts beyond the data, and a height far beyond the
+    // compressed rows, fail without allocating for the claimed image.
+    let mut huge_count = LIBTIFF_GROUP4;
+    huge_count[0xB4..0xB8].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        tiff::decode(&huge_count[..]),
+        Err(DecodingError::UnexpectedEof)
+    ));
+    // Image length and rows per strip as longs, one strip for all rows.
+    let mut huge_height = LIBTIFF_GROUP4;
+    huge_height[0x60..0x64].copy_from_slice(&(1u32 << 24).to_le_bytes());
+    huge_height[0x5A] = 4;
+    huge_height[0xA2] = 4;
+    huge_height[0xA8..0xAC].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        tiff::decode(&huge_height[..]),
+        Err(DecodingError::UnexpectedEof)
+    ));
+
+    // Eight bits per sample.
+    let mut gray = data.clone();
+    let entry = gray.len() - 4 - 12 * 9 + 2 * 12;
+    gray[entry + 8] = 8;
+    assert!(matches!(
+        tiff::decode(gray.as_slice()),
+        Err(DecodingError::Unsupported(_))
+    ));
+
+    // The last IFD points back at itself.
+    let mut looped = data;
+    let len = looped.len();
+    let first = looped[4..8].to_vec();
+    looped[len - 4..].copy_from_slice(&first);
+    assert!(matches!(
+        tiff::decode(looped.as_slice()),
+        Err(DecodingError::InvalidHeader(_))
+    ));
+}
@@ -0,0 +1,134 @@
+use super::*;
+use crate::codecs::svg::{self, SvgOptions};
+
+fn disk(size: u32, radius: f64) -> BinaryImage {
+    let mut image = BinaryImage::new(size, size);
+    let center = f64::from(size) / 2.0;
+    for y in 0..size {
+        for x in 0..size {
+            let (dx, dy) = (f64::from(x) + 0.5 - center, f64::from(y) + 0.5 - center);
+            if dx.hypot(dy) < radius {
+                image.put_pixel(x, y, Bit(true));
+            }
+        }
+    }
+    image
+}
+
+#[test]
+fn test_square() {
+    // A large rectangle keeps four sharp corners at its pixel corners; small
+    // ones would be rounded.
+    let mut image = BinaryImage::new(40, 40);
+    image.sub_view_mut(2, 3, 30, 20).fill(Bit(true));
+
+    let paths = image.trace(&TraceOptions::default());
+    assert_eq!(paths.len(), 1);
+    let path = &paths[0];
+    assert!(!path.is_hole);
+    assert_eq!(path.segments.len(), 4);
+    let mut vertices = Vec::new();
+    for segment in &path.segments {
+        let Segment::Corner { vertex, .. } = *segment else {
+            panic!("Expected only corners, got {segment:?}");
+        };
+        vertices.push(vertex);
+    }
+    for corner in [(2.0, 3.0), (32.0, 3.0), (32.0, 23.0), (2.0, 23.0)] {
+        assert!(
+            vertices.contains(&corner),
+            "Missing {corner:?} in {vertices:?}"
+        );
+    }
+}
+
+#[test]
+fn test_disk() {
+    let image = disk(40, 15.0);
+    let paths = image.trace(&TraceOptions::default());
+    assert_eq!(paths.len(), 1);
+    let segments = &paths[0].segments;
+    assert!(segments
+        .iter()
+        .all(|segment| matches!(segment, Segment::Curve { .. })));
+    for segment in segments {
+        let (x, y) = segment.end();
+        assert!(((x - 20.0).hypot(y - 20.0) - 15.0).abs() < 0.5);
+    }
+
+    let unoptimized = image.trace(&TraceOptions {
+        optimize_curves: false,
+        ..TraceOptions::default()
+    });
+    assert!(unoptimized[0].segments.len() > segments.len());
+
+    let polygon = image.trace(&TraceOptions {
+        alpha_max: 0.0,
+        ..TraceOptions::default()
+    });
+    assert!(polygon[0]
+        .segments
+        .iter()
+        .all(|segment| matches!(segment, Segment::Corner { .. })));
+}
+
+#[test]
+fn test_ring_and_specks() {
+    let mut image = disk(40, 15.0);
+    image.sub_view_mut(15, 15, 10, 10).fill(Bit(false));
+    image.put_pixel(0, 0, Bit(true));
+    image.sub_view_mut(37, 37, 2, 2).fill(Bit(true));
+    image.put_pixel(20, 20, Bit(true));
+
+    let paths = image.trace(&TraceOptions::default());
+    assert_eq!(paths.len(), 3);
+    assert_eq!(paths.iter().filter(|path| path.is_hole).count(), 1);
+
+    let paths = image.trace(&TraceOptions {
+        turd_size: 0,
+        ..TraceOptions::default()
+    });
+    assert_eq!(paths.len(), 5);
+}
+
+#[test]
+fn test_svg() {
+    let mut image = disk(60, 20.0);
+    image.sub_view_mut(0, 0, 8, 8).fill(Bit(true));
+
+    let path = &image.trace(&TraceOptions::default())[0];
+    let data = path.svg_data();
+    assert_eq!(data, "M4 0L8 0L8 4L8 8L4 8L0 8L0 4L0 0L4 0Z");
+
+    let document = svg::encode_traced(
+        &image,
+        &SvgOptions {
+            group_components: true,
+            ..SvgOptions::default()
+        },
+        &TraceOptions::default(),
+    );
+    assert!(document.contains(r#"<g id="component-1">"#));
+    assert!(document.contains(r#"<g id="component-2">"#));
+    assert!(document.contains('C'));
+}
+
+#[test]
+fn test_noise() {
+    for seed in 1..8 {
+        let image = noise(37, 29, seed);
+        for connectivity in [Connectivity::Four, Connectivity::Eight] {
+            let options = TraceOptions {
+                connectivity,
+                ..TraceOptions::default()
+            };
+            let paths = image.trace(&options);
+            let outlines = image.outlines(connectivity);
+            let kept = outlines
+                .iter()
+                .filter(|outline| outline.signed_area().abs() > 2);
+            assert_eq!(paths.len(), kept.count());
+            assert!(paths.iter().all(|path| !path.segments.is_empty()));
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use super::*;
+
+#[test]
+fn test_sub_view() {
+    let image = BinaryImage::from_raw(4, 4, &DATA);
+    let view = image.sub_view(1, 1, 3, 2);
+
+    assert_eq!(view.dimensions(), (3, 2));
+    assert_eq!(view.offsets(), (1, 1));
+    assert!(*view.get_pixel(0, 0) && *view.get_pixel(1, 0) && !*view.get_pixel(2, 1));
+
+    let nested = view.sub_view(1, 0, 2, 2);
+    assert_eq!(nested.offsets(), (2, 1));
+    let nested = nested.to_image();
+    assert_eq!(nested.dimensions(), (2, 2));
+    assert!(nested.get_pixel(0, 0).0 && !nested.get_pixel(1, 1).0);
+}
+
+#[test]
+fn test_sub_view_of_binary_view() {
+    let image: ImageBuffer<Luma<u8>, &[u8]> = ImageBuffer::from_raw(4, 4, DATA.as_ref()).unwrap();
+    let view = BinaryView::Ref(&image);
+    let sub = view.sub_view(3, 3, 1, 1);
+
+    assert!(*sub.get_pixel(0, 0));
+    assert_eq!(BinaryImage::from(sub).dimensions(), (1, 1));
+}
+
+#[test]
+fn test_sub_view_mut() {
+    let mut image = BinaryImage::from_raw(4, 4, &DATA);
+    let mut view = image.sub_view_mut(2, 0, 2, 4);
+    view.fill(Bit(true));
+    view.put_pixel(0, 3, Bit(false));
+    view.sub_view_mut(1, 1, 1, 1).fill(Bit(false));
+    assert!(!*view.sub_view(1, 1, 1, 1).get_pixel(0, 0));
+
+    let expected: [u8; 16] = [
+        1, 0, 1, 1, //
+        0, 1, 1, 0, //
+        0, 1, 1, 1, //
+        0, 0, 0, 1, //
+    ];
+    for ((_, _, pixel), expected) in image.pixels().zip(expected) {
+        assert_eq!(*pixel, expected > 0);
+    }
+
+    let mut target = BinaryImage::new(3, 3);
+    target
+        .sub_view_mut(1, 1, 2, 2)
+        .copy_from_image(&image.crop(0, 0, 2, 2));
+    assert!(target.get_pixel(1, 1).0 && target.get_pixel(2, 2).0 && !target.get_pixel(2, 1).0);
+}
+
+#[test]
+#[should_panic(expected = "Sub view out of bounds")]
+fn test_sub_view_out_of_bounds() {
+    let image = BinaryImage::new(4, 4);
+    let _ = image.sub_view(2, 2, 3, 1);
+}
+
+#[test]
+#[should_panic(expected = "cannot be borrowed mutably")]
+#[allow(deprecated)]
+fn test_sub_view_get_pixel_mut() {
+    let mut image = BinaryImage::new(4, 4);
+    let _ = image.sub_view_mut(1, 1, 2, 2).get_pixel_mut(0, 0);
+}
+
+#[test]
+fn test_transformed_view() {
+    let image = BinaryImage::from_raw(4, 4, &DATA);
+
+    let rotated = image.rotated90();
+    assert_eq!(rotated.dimensions(), (4, 4));
+    assert!(*rotated.get_pixel(3, 0) && *rotated.get_pixel(0, 3) && *rotated.get_pixel(1, 1));
+    assert!(!*rotated.get_pixel(0, 0));
+
+    assert_same(&image.flipped_h(), &image.flip_horizontal());
+    assert_same(&image.flipped_v(), &image.flip_vertical());
+    assert_same(&image.rotated90().rotated270(), &image);
+    assert_same(&image.rotated90().rotated90(), &image.rotated180());
+    assert_same(&image.transposed().transposed(), &image);
+    assert_same(&image.inverted().inverted().flipped_h().flipped_h(), &image);
+
+    let inverted = image.inverted();
+    for (x, y, pixel) in image.pixels() {
+        assert_eq!(!pixel, inverted.get_pixel(x, y));
+    }
+}
+
+#[test]
+fn test_transformed_view_non_square() {
+    let image = noise(45, 70, 7);
+    let rotated = image.rotated90();
+
+    assert_eq!(rotated.dimensions(), (70, 45));
+    for (x, y, pixel) in image.pixels() {
+        assert_eq!(pixel, rotated.get_pixel(69 - y, x));
+    }
+}
+
+#[test]
+fn test_materialize() {
+    let image = noise(45, 70, 11);
+    let luma: ImageBuffer<Luma<u8>, Vec<u8>> =
+        ImageBuffer::from_fn(45, 70, |x, y| Luma([u8::from(*image.get_pixel(x, y))]));
+    let view = BinaryView::Ref(&luma);
+
+    let transforms: [fn(TransformedView<'_, BinaryImage>) -> TransformedView<'_, BinaryImage>; 6] = [
+        |view| view,
+        |view| view.inverted(),
+        |view| view.rotated90(),
+        |view| view.transposed().inverted(),
+        |view| view.rotated270().flipped_v(),
+        |view| view.rotated180(),
+    ];
+    for transform in transforms {
+        let lazy = transform(image.transformed());
+        let packed = lazy.materialize();
+        assert_same(&packed, &lazy);
+    }
+
+    let lazy = view.rotated90().inverted();
+    assert_same(&lazy.materialize(), &lazy);
+    assert_same(
+        &image.sub_view(3, 5, 40, 33).materialize(),
+        &image.sub_view(3, 5, 40, 33),
+    );
+}
+
+#[test]
+fn test_expression_views() {
+    let a = noise(37, 21, 3);
+    let b = noise(37, 21, 5);
+    let c = noise(37, 21, 9);
+    let luma: ImageBuffer<Luma<u8>, Vec<u8>> =
+        ImageBuffer::from_fn(37, 21, |x, y| Luma([u8::from(*c.get_pixel(x, y)) * 200]));
+
+    // a & !b | c
+    let expr = a
+        .transformed()
+        .and(b.transformed().not())
+        .or(BinaryView::Ref(&luma));
+    for (x, y, pixel) in expr.pixels() {
+        let expected = (*a.get_pixel(x, y) && !*b.get_pixel(x, y)) || *c.get_pixel(x, y);
+        assert_eq!(*pixel, expected);
+    }
+    assert_same(&expr.materialize(), &expr);
+
+    let expr = a.transformed().xor(b.flipped_h()).not();
+    for (x, y, pixel) in expr.pixels() {
+        assert_eq!(*pixel, a.get_pixel(x, y) == b.get_pixel(36 - x, y));
+    }
+    assert_same(&expr.materialize(), &expr);
+}
+
+#[test]
+#[should_panic(expected = "Dimensions mismatch")]
+fn test_expression_dimensions_mismatch() {
+    let a = BinaryImage::new(2, 3);
+    let b = BinaryImage::new(3, 2);
+    let _ = a.transformed().and(b.transformed());
+}
@@ -0,0 +1,75 @@
+use super::*;
+use crate::codecs::{xbm, DecodingError};
+
+const SOURCE: &str = "/* Created with bitmap */
+#define test_width 4
+#define test_height 4 // rows
+#define test_x_hot 1
+#define test_y_hot 2
+static unsigned char test_bits[] = {
+   0x0e, 0x09, 0x0d, 0x07 };
+";
+
+#[test]
+fn test_decode() {
+    let xbm = xbm::decode(SOURCE).unwrap();
+
+    assert_eq!(xbm.name, "test");
+    assert_eq!(xbm.hotspot, Some((1, 2)));
+    assert_same(&xbm.image, &BinaryImage::from_raw(4, 4, &DATA));
+}
+
+#[test]
+fn test_decode_x10() {
+    let source = "#define old_width 20\n#define old_height 1\n\
+                  static short old_bits[] = {0xfffe, 0x000f};";
+    let xbm = xbm::decode(source).unwrap();
+
+    assert_eq!(xbm.hotspot, None);
+    assert!(*xbm.image.get_pixel(0, 0));
+    assert!((1..20).all(|x| !*xbm.image.get_pixel(x, 0)));
+}
+
+#[test]
+fn test_encode() {
+    let image = BinaryImage::from_raw(4, 4, &DATA);
+    assert_eq!(
+        image.to_xbm("test", Some((1, 2))),
+        "#define test_width 4\n\
+         #define test_height 4\n\
+         #define test_x_hot 1\n\
+         #define test_y_hot 2\n\
+         static unsigned char test_bits[] = {\n   0x0e, 0x09, 0x0d, 0x07 };\n"
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let image = noise(29, 13, 4);
+    let source = xbm::encode(&image, "2 bad-name", None);
+    assert!(source.starts_with("#define _2_bad_name_width 29\n"));
+
+    let xbm = xbm::decode(&source).unwrap();
+    assert_eq!(xbm.name, "_2_bad_name");
+    assert_same(&xbm.image, &image);
+}
+
+#[test]
+fn test_malformed() {
+    assert!(matches!(
+        xbm::decode("#define a_height 1\nstatic char a_bits[] = {0};"),
+        Err(DecodingError::InvalidHeader(_))
+    ));
+    assert!(matches!(
+        xbm::decode("#define a_width 9\n#define a_height 1\nstatic char a_bits[] = {0x00};"),
+        Err(DecodingError::InvalidData(_))
+    ));
+    assert!(matches!(
+        xbm::decode("#define a_width 8\n#define a_height 1\nstatic char a_bits[] = {0x100};"),
+        Err(DecodingError::InvalidData(_))
+    ));
+    assert!(matches!(
+        xbm::decode("#define a_width 8\n#define a_height 1\nstatic char a_bits[] = {0x00,"),
+        Err(DecodingError::UnexpectedEof)
+    ));
+}
@@ -0,0 +1,912 @@
+//! Vectorization of outlines into smooth curves, following Potrace.
+//!
+//! Each outline is approximated by the polygon with the fewest vertices whose
+//! edges stay within half a pixel of it, the vertices are moved to best fit
+//! the outline, and corners are rounded with cubic Bézier curves unless they
+//! are sharper than `alpha_max`. Runs of curves are then joined into single
+//! curves where that stays within `opt_tolerance`. See Peter Selinger,
+//! "Potrace: a polygon-based tracing algorithm", 2003.
+#![allow(clippy::cast_precision_loss, clippy::many_single_char_names)]
+
+use std::fmt::Write;
+
+use crate::{BinaryImage, Connectivity};
+
+type Point = (f64, f64);
+
+/// Options for [`BinaryImage::trace`], with the defaults of Potrace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraceOptions {
+    /// Outlines enclosing at most this many pixels are dropped, removing
+    /// specks and filling small holes.
+    pub turd_size: u64,
+    /// Corners are kept sharp where the curve would bend more than this,
+    /// from 0 for a polygon to 4/3 for no corners at all.
+    pub alpha_max: f64,
+    /// Join consecutive curves where possible.
+    pub optimize_curves: bool,
+    /// How far joined curves may stray from the original ones, in pixels.
+    pub opt_tolerance: f64,
+    /// Whether pixels touching only at a corner share an outline.
+    pub connectivity: Connectivity,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        Self {
+            turd_size: 2,
+            alpha_max: 1.0,
+            optimize_curves: true,
+            opt_tolerance: 0.2,
+            connectivity: Connectivity::Eight,
+        }
+    }
+}
+
+/// A piece of a [`TracedPath`], starting where the previous one ends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    /// A straight line to `vertex` and another one on to `end`.
+    Corner { vertex: Point, end: Point },
+    /// A cubic Bézier curve to `end`.
+    Curve {
+        control1: Point,
+        control2: Point,
+        end: Point,
+    },
+}
+
+impl Segment {
+    #[must_use]
+    pub fn end(&self) -> Point {
+        match *self {
+            Segment::Corner { end, .. } | Segment::Curve { end, .. } => end,
+        }
+    }
+}
+
+/// A closed path traced from one outline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracedPath {
+    /// The segments in the direction of the outline; the path starts at the
+    /// end of the last one.
+    pub segments: Vec<Segment>,
+    /// Whether the path bounds a hole, see [`Outline::is_hole`](crate::Outline::is_hole).
+    pub is_hole: bool,
+    /// A set pixel bordering the path, see
+    /// [`Outline::inner_pixel`](crate::Outline::inner_pixel).
+    pub pixel: (u32, u32),
+}
+
+impl TracedPath {
+    /// The path as SVG path data, with coordinates rounded to three
+    /// decimals.
+    #[must_use]
+    pub fn svg_data(&self) -> String {
+        let mut data = String::new();
+        let Some(last) = self.segments.last() else {
+            return data;
+        };
+        // Writing to a `String` cannot fail.
+        let _ = write!(data, "M{}", coordinates(last.end()));
+        for segment in &self.segments {
+            let _ = match *segment {
+                Segment::Corner { vertex, end } => {
+                    write!(data, "L{}L{}", coordinates(vertex), coordinates(end))
+                }
+                Segment::Curve {
+                    control1,
+                    control2,
+                    end,
+                } => write!(
+                    data,
+                    "C{} {} {}",
+                    coordinates(control1),
+                    coordinates(control2),
+                    coordinates(end)
+                ),
+            };
+        }
+        data.push('Z');
+        data
+    }
+}
+
+fn coordinates((x, y): Point) -> String {
+    let number = |value: f64| {
+        let text = format!("{:.3}", (value * 1000.0).round() / 1000.0);
+        let text = text.trim_end_matches('0').trim_end_matches('.');
+        if text == "-0" {
+            "0".to_owned()
+        } else {
+            text.to_owned()
+        }
+    };
+    format!("{} {}", number(x), number(y))
+}
+
+impl BinaryImage {
+    /// Traces the outlines of the set pixels into smooth closed paths.
+    ///
+    /// Filling the paths with the even-odd rule draws the image.
+    #[must_use]
+    pub fn trace(&self, options: &TraceOptions) -> Vec<TracedPath> {
+        self.outlines(options.connectivity)
+            .into_iter()
+            .filter(|outline| outline.signed_area().unsigned_abs() > options.turd_size)
+            .map(|outline| {
+                // Every corner of the pixel grid along the outline.
+                let mut points = Vec::new();
+                let corners = &outline.points;
+                for (i, &(x, y)) in corners.iter().enumerate() {
+                    let (next_x, next_y) = corners[(i + 1) % corners.len()];
+                    let (x, y) = (i64::from(x), i64::from(y));
+                    let (next_x, next_y) = (i64::from(next_x), i64::from(next_y));
+                    let steps = (next_x - x).abs() + (next_y - y).abs();
+                    let (dx, dy) = ((next_x - x).signum(), (next_y - y).signum());
+                    points.extend((0..steps).map(|step| (x + dx * step, y + dy * step)));
+                }
+                let path = Path::new(points);
+                let mut curve = path.smooth(&path.polygon(), options.alpha_max);
+                if options.optimize_curves {
+                    curve = curve.optimize(options.opt_tolerance);
+                }
+                TracedPath {
+                    segments: curve.segments(),
+                    is_hole: outline.is_hole(),
+                    pixel: outline.inner_pixel(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Whether `b` lies in the cyclic range from `a` up to but excluding `c`.
+fn cyclic(a: usize, b: usize, c: usize) -> bool {
+    if a <= c {
+        a <= b && b < c
+    } else {
+        a <= b || b < c
+    }
+}
+
+fn xprod(a: (i64, i64), b: (i64, i64)) -> i64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+/// Twice the signed area of the triangle `p0`, `p1`, `p2`.
+fn dpara(p0: Point, p1: Point, p2: Point) -> f64 {
+    (p1.0 - p0.0) * (p2.1 - p0.1) - (p2.0 - p0.0) * (p1.1 - p0.1)
+}
+
+/// Cross product of `p1 - p0` and `p3 - p2`.
+fn cprod(p0: Point, p1: Point, p2: Point, p3: Point) -> f64 {
+    (p1.0 - p0.0) * (p3.1 - p2.1) - (p3.0 - p2.0) * (p1.1 - p0.1)
+}
+
+/// Dot product of `p1 - p0` and `p3 - p2`.
+fn iprod(p0: Point, p1: Point, p2: Point, p3: Point) -> f64 {
+    (p1.0 - p0.0) * (p3.0 - p2.0) + (p1.1 - p0.1) * (p3.1 - p2.1)
+}
+
+fn distance(p: Point, q: Point) -> f64 {
+    (p.0 - q.0).hypot(p.1 - q.1)
+}
+
+/// The point `lambda` of the way from `a` to `b`.
+fn interval(lambda: f64, a: Point, b: Point) -> Point {
+    (a.0 + lambda * (b.0 - a.0), a.1 + lambda * (b.1 - a.1))
+}
+
+fn bezier(t: f64, p0: Point, p1: Point, p2: Point, p3: Point) -> Point {
+    let s = 1.0 - t;
+    let at = |a: f64, b: f64, c: f64, d: f64| {
+        s * s * s * a + 3.0 * s * s * t * b + 3.0 * t * t * s * c + t * t * t * d
+    };
+    (at(p0.0, p1.0, p2.0, p3.0), at(p0.1, p1.1, p2.1, p3.1))
+}
+
+/// The parameter in `[0, 1]` where the Bézier curve is parallel to
+/// `q1 - q0`, if any.
+fn tangent(p0: Point, p1: Point, p2: Point, p3: Point, q0: Point, q1: Point) -> Option<f64> {
+    let a = cprod(p0, p1, q0, q1);
+    let b = cprod(p1, p2, q0, q1);
+    let c = cprod(p2, p3, q0, q1);
+    let (a, b, c) = (a - 2.0 * b + c, -2.0 * a + 2.0 * b, a);
+    let discriminant = b * b - 4.0 * a * c;
+    if a == 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    [(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+        .into_iter()
+        .find(|t| (0.0..=1.0).contains(t))
+}
+
+/// An outline as the sequence of all grid points along it.
+struct Path {
+    points: Vec<(i64, i64)>,
+    /// Prefix sums of `x`, `y`, `xy`, `x²` and `y²` relative to the first
+    /// point.
+    sums: Vec<[f64; 5]>,
+}
+
+impl Path {
+    fn new(points: Vec<(i64, i64)>) -> Self {
+        let (x0, y0) = points[0];
+        let mut sums = Vec::with_capacity(points.len() + 1);
+        let mut sum = [0.0; 5];
+        sums.push(sum);
+        for &(x, y) in &points {
+            let (x, y) = ((x - x0) as f64, (y - y0) as f64);
+            sum = [
+                sum[0] + x,
+                sum[1] + y,
+                sum[2] + x * y,
+                sum[3] + x * x,
+                sum[4] + y * y,
+            ];
+            sums.push(sum);
+        }
+        Self { points, sums }
+    }
+
+    fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    fn point(&self, i: usize) -> Point {
+        let (x, y) = self.points[i % self.len()];
+        (x as f64, y as f64)
+    }
+
+    /// For each point, the furthest point such that the path between them is
+    /// straight, that is within half a pixel of a line.
+    fn longest_straight(&self) -> Vec<usize> {
+        let n = self.len();
+        let pt = &self.points;
+        let direction = |from: (i64, i64), to: (i64, i64)| {
+            usize::try_from((3 + 3 * (to.0 - from.0).signum() + (to.1 - from.1).signum()) / 2)
+                .unwrap_or_default()
+        };
+        // The next corner after each point.
+        let mut next_corner = vec![0; n];
+        let mut k = 0;
+        for i in (0..n).rev() {
+            if pt[i].0 != pt[k].0 && pt[i].1 != pt[k].1 {
+                k = i + 1;
+            }
+            next_corner[i] = k;
+        }
+
+        let mut pivots = vec![0; n];
+        for i in (0..n).rev() {
+            let mut directions = [0; 4];
+            directions[direction(pt[i], pt[(i + 1) % n])] += 1;
+            let mut constraint = [(0, 0); 2];
+            let mut k = next_corner[i];
+            let mut k1 = i;
+            let mut found = false;
+            loop {
+                directions[direction(pt[k1], pt[k])] += 1;
+                // A straight path never goes in all four directions.
+                if directions.iter().all(|&count| count > 0) {
+                    pivots[i] = k1;
+                    found = true;
+                    break;
+                }
+                let current = (pt[k].0 - pt[i].0, pt[k].1 - pt[i].1);
+                if xprod(constraint[0], current) < 0 || xprod(constraint[1], current) > 0 {
+                    break;
+                }
+                if current.0.abs() > 1 || current.1.abs() > 1 {
+                    let (x, y) = current;
+                    let offset = (
+                        x + if y >= 0 && (y > 0 || x < 0) { 1 } else { -1 },
+                        y + if x <= 0 && (x < 0 || y < 0) { 1 } else { -1 },
+                    );
+                    if xprod(constraint[0], offset) >= 0 {
+                        constraint[0] = offset;
+                    }
+                    let offset = (
+                        x + if y <= 0 && (y < 0 || x < 0) { 1 } else { -1 },
+                        y + if x >= 0 && (x > 0 || y < 0) { 1 } else { -1 },
+                    );
+                    if xprod(constraint[1], offset) <= 0 {
+                        constraint[1] = offset;
+                    }
+                }
+                k1 = k;
+                k = next_corner[k1];
+                if !cyclic(k, i, k1) {
+                    break;
+                }
+            }
+            if !found {
+                // Find how far the last edge can be followed within the
+                // constraints.
+                let dk = ((pt[k].0 - pt[k1].0).signum(), (pt[k].1 - pt[k1].1).signum());
+                let current = (pt[k1].0 - pt[i].0, pt[k1].1 - pt[i].1);
+                let a = xprod(constraint[0], current);
+                let b = xprod(constraint[0], dk);
+                let c = xprod(constraint[1], current);
+                let d = xprod(constraint[1], dk);
+                let mut j = 10_000_000;
+                if b < 0 {
+                    j = a.div_euclid(-b);
+                }
+                if d > 0 {
+                    j = j.min((-c).div_euclid(d));
+                }
+                let n = n.cast_signed() as i64;
+                pivots[i] = usize::try_from((k1.cast_signed() as i64 + j).rem_euclid(n))
+                    .unwrap_or_default();
+            }
+        }
+
+        let mut longest = vec![0; n];
+        let mut j = pivots[n - 1];
+        longest[n - 1] = j;
+        for i in (0..n - 1).rev() {
+            if cyclic(i + 1, pivots[i], j) {
+                j = pivots[i];
+            }
+            longest[i] = j;
+        }
+        let mut i = n - 1;
+        while cyclic((i + 1) % n, j, longest[i]) {
+            longest[i] = j;
+            i -= 1;
+        }
+        longest
+    }
+
+    /// The sums over the points from `i` to `j` inclusive, `j` possibly
+    /// wrapping around once or more.
+    fn sums_between(&self, i: usize, j: usize) -> ([f64; 5], f64) {
+        let n = self.len();
+        let rounds = j / n;
+        let j = j % n;
+        let (i, rounds) = if i >= n {
+            (i - n, rounds - 1)
+        } else {
+            (i, rounds)
+        };
+        let mut sum = [0.0; 5];
+        for (k, value) in sum.iter_mut().enumerate() {
+            *value = self.sums[j + 1][k] - self.sums[i][k] + rounds as f64 * self.sums[n][k];
+        }
+        (sum, (j + 1 + rounds * n - i) as f64)
+    }
+
+    /// The penalty of an edge from point `i` to `j`, `j` possibly past the
+    /// end: the root mean square distance of the points between them to the
+    /// edge, scaled by its length.
+    fn penalty(&self, i: usize, j: usize) -> f64 {
+        let ([x, y, xy, x2, y2], k) = self.sums_between(i, j);
+        let (x0, y0) = self.point(0);
+        let (pi, pj) = (self.point(i), self.point(j));
+        let px = f64::midpoint(pi.0, pj.0) - x0;
+        let py = f64::midpoint(pi.1, pj.1) - y0;
+        let ey = pj.0 - pi.0;
+        let ex = -(pj.1 - pi.1);
+        let a = (x2 - 2.0 * x * px) / k + px * px;
+        let b = (xy - x * py - y * px) / k + px * py;
+        let c = (y2 - 2.0 * y * py) / k + py * py;
+        (ex * ex * a + 2.0 * ex * ey * b + ey * ey * c).sqrt()
+    }
+
+    /// Indices of the points of the optimal polygon: the fewest straight
+    /// edges, with the least penalty among those.
+    fn polygon(&self) -> Vec<usize> {
+        let n = self.len();
+        let longest = self.longest_straight();
+        // The furthest and nearest points an edge from or to each point may
+        // reach.
+        let mut clip0 = vec![0; n];
+        for (i, clip) in clip0.iter_mut().enumerate() {
+            let mut c = (longest[(i + n - 1) % n] + n - 1) % n;
+            if c == i {
+                c = (i + 1) % n;
+            }
+            *clip = if c < i { n } else { c };
+        }
+        let mut clip1 = vec![0; n + 1];
+        let mut j = 1;
+        for (i, &clip) in clip0.iter().enumerate() {
+            while j <= clip {
+                clip1[j] = i;
+                j += 1;
+            }
+        }
+        // Bounds of the points reachable with each number of edges.
+        let mut seg0 = vec![0; n + 1];
+        let mut i = 0;
+        let mut m = 0;
+        while i < n {
+            seg0[m] = i;
+            i = clip0[i];
+            m += 1;
+        }
+        seg0[m] = n;
+        let mut seg1 = vec![0; m + 1];
+        let mut i = n;
+        for j in (1..=m).rev() {
+            seg1[j] = i;
+            i = clip1[i];
+        }
+
+        let mut penalties = vec![0.0; n + 1];
+        let mut previous = vec![0; n + 1];
+        for j in 1..=m {
+            for i in seg1[j]..=seg0[j] {
+                let mut best = f64::INFINITY;
+                for k in (clip1[i]..=seg0[j - 1]).rev() {
+                    let penalty = self.penalty(k, i) + penalties[k];
+                    if penalty < best {
+                        previous[i] = k;
+                        best = penalty;
+                    }
+                }
+                penalties[i] = best;
+            }
+        }
+        let mut polygon = vec![0; m];
+        let mut i = n;
+        for vertex in polygon.iter_mut().rev() {
+            i = previous[i];
+            *vertex = i;
+        }
+        polygon
+    }
+
+    /// The center and direction of the line best fitting the points from
+    /// `i` to `j`.
+    fn fit_line(&self, i: usize, j: usize) -> (Point, Point) {
+        let ([x, y, xy, x2, y2], k) = self.sums_between(i, j);
+        let center = (x / k, y / k);
+        let mut a = (x2 - x * x / k) / k;
+        let b = (xy - x * y / k) / k;
+        let mut c = (y2 - y * y / k) / k;
+        // The larger eigenvalue of the covariance.
+        let lambda = (a + c + ((a - c) * (a - c) + 4.0 * b * b).sqrt()) / 2.0;
+        a -= lambda;
+        c -= lambda;
+        let direction = if a.abs() >= c.abs() {
+            let l = a.hypot(b);
+            if l == 0.0 {
+                (0.0, 0.0)
+            } else {
+                (-b / l, a / l)
+            }
+        } else {
+            let l = c.hypot(b);
+            if l == 0.0 {
+                (0.0, 0.0)
+            } else {
+                (-c / l, b / l)
+            }
+        };
+        (center, direction)
+    }
+
+    /// Moves the polygon vertices to where the lines fitting the adjacent
+    /// parts of the path meet, within half a pixel of the original vertex.
+    fn adjust_vertices(&self, polygon: &[usize]) -> Vec<Point> {
+        let n = self.len();
+        let m = polygon.len();
+        let (x0, y0) = self.point(0);
+        // The squared distance to the fitted line of each edge, as a
+        // quadratic form over (x, y, 1).
+        let forms: Vec<[[f64; 3]; 3]> = (0..m)
+            .map(|i| {
+                let next = polygon[(i + 1) % m];
+                let j = (next + n - polygon[i]) % n + polygon[i];
+                let (center, direction) = self.fit_line(polygon[i], j);
+                let d = direction.0 * direction.0 + direction.1 * direction.1;
+                let mut form = [[0.0; 3]; 3];
+                if d != 0.0 {
+                    let v = [
+                        direction.1,
+                        -direction.0,
+                        direction.0 * center.1 - direction.1 * center.0,
+                    ];
+                    for (l, row) in form.iter_mut().enumerate() {
+                        for (k, value) in row.iter_mut().enumerate() {
+                            *value = v[l] * v[k] / d;
+                        }
+                    }
+                }
+                form
+            })
+            .collect();
+
+        (0..m)
+            .map(|i| {
+                let (px, py) = self.point(polygon[i]);
+                let previous = &forms[(i + m - 1) % m];
+                let mut q = [[0.0; 3]; 3];
+                for l in 0..3 {
+                    for k in 0..3 {
+                        q[l][k] = previous[l][k] + forms[i][l][k];
+                    }
+                }
+                let (x, y) = minimize(q, (px - x0, py - y0));
+                (x + x0, y + y0)
+            })
+            .collect()
+    }
+
+    /// Rounds the corners of the adjusted polygon.
+    fn smooth(&self, polygon: &[usize], alpha_max: f64) -> Curve {
+        let vertices = self.adjust_vertices(polygon);
+        let m = vertices.len();
+        let mut curve = Curve {
+            corner: vec![false; m],
+            controls: vec![[(0.0, 0.0); 3]; m],
+            alpha: vec![0.0; m],
+            vertices,
+        };
+        for i in 0..m {
+            let j = (i + 1) % m;
+            let k = (i + 2) % m;
+            let (vi, vj, vk) = (curve.vertices[i], curve.vertices[j], curve.vertices[k]);
+            let midpoint = interval(0.5, vk, vj);
+            // The corner is as sharp as vj is far from the line vi-vk,
+            // measured in the L-infinity norm.
+            let normal = (-(vk.1 - vi.1).signum(), (vk.0 - vi.0).signum());
+            let denominator = normal.1 * (vk.0 - vi.0) - normal.0 * (vk.1 - vi.1);
+            let mut alpha = if denominator == 0.0 {
+                4.0 / 3.0
+            } else {
+                let dd = (dpara(vi, vj, vk) / denominator).abs();
+                let alpha = if dd > 1.0 { 1.0 - 1.0 / dd } else { 0.0 };
+                alpha / 0.75
+            };
+            if alpha >= alpha_max {
+                curve.corner[j] = true;
+                curve.controls[j] = [vj, vj, midpoint];
+            } else {
+                alpha = alpha.clamp(0.55, 1.0);
+                let control1 = interval(0.5 + 0.5 * alpha, vi, vj);
+                let control2 = interval(0.5 + 0.5 * alpha, vk, vj);
+                curve.controls[j] = [control1, control2, midpoint];
+            }
+            curve.alpha[j] = alpha;
+        }
+        curve
+    }
+}
+
+/// The point within half a pixel of `s` in both coordinates minimizing the
+/// quadratic form `q`.
+fn minimize(mut q: [[f64; 3]; 3], s: Point) -> Point {
+    let evaluate = |q: &[[f64; 3]; 3], (x, y): Point| {
+        let v = [x, y, 1.0];
+        (0..3)
+            .map(|l| (0..3).map(|k| v[l] * q[l][k] * v[k]).sum::<f64>())
+            .sum::<f64>()
+    };
+    let w = loop {
+        let det = q[0][0] * q[1][1] - q[0][1] * q[1][0];
+        if det != 0.0 {
+            break (
+                (-q[0][2] * q[1][1] + q[1][2] * q[0][1]) / det,
+                (q[0][2] * q[1][0] - q[1][2] * q[0][0]) / det,
+            );
+        }
+        // Parallel lines: add a line through the vertex.
+        let v = if q[0][0] > q[1][1] {
+            [-q[0][1], q[0][0]]
+        } else if q[1][1] != 0.0 {
+            [-q[1][1], q[1][0]]
+        } else {
+            [1.0, 0.0]
+        };
+        let d = v[0] * v[0] + v[1] * v[1];
+        let v = [v[0], v[1], -v[1] * s.1 - v[0] * s.0];
+        for l in 0..3 {
+            for k in 0..3 {
+                q[l][k] += v[l] * v[k] / d;
+            }
+        }
+    };
+    if (w.0 - s.0).abs() <= 0.5 && (w.1 - s.1).abs() <= 0.5 {
+        return w;
+    }
+
+    // Otherwise find the minimum on the boundary of the square.
+    let mut min = evaluate(&q, s);
+    let mut best = s;
+    let mut consider = |candidate: Point| {
+        let value = evaluate(&q, candidate);
+        if value < min {
+            min = value;
+            best = candidate;
+        }
+    };
+    for z in [-0.5, 0.5] {
+        if q[0][0] != 0.0 {
+            let y = s.1 + z;
+            let x = -(q[0][1] * y + q[0][2]) / q[0][0];
+            if (x - s.0).abs() <= 0.5 {
+                consider((x, y));
+            }
+        }
+    }
+    for z in [-0.5, 0.5] {
+        if q[1][1] != 0.0 {
+            let x = s.0 + z;
+            let y = -(q[1][0] * x + q[1][2]) / q[1][1];
+            if (y - s.1).abs() <= 0.5 {
+                consider((x, y));
+            }
+        }
+    }
+    for dx in [-0.5, 0.5] {
+        for dy in [-0.5, 0.5] {
+            consider((s.0 + dx, s.1 + dy));
+        }
+    }
+    best
+}
+
+/// Segments around a polygon: segment `i` ends at `controls[i][2]`,
+/// between vertex `i` and the next.
+struct Curve {
+    vertices: Vec<Point>,
+    corner: Vec<bool>,
+    controls: Vec<[Point; 3]>,
+    alpha: Vec<f64>,
+}
+
+/// A candidate curve replacing several segments.
+#[derive(Default, Clone, Copy)]
+struct Joined {
+    penalty: f64,
+    controls: [Point; 2],
+    alpha: f64,
+    s: f64,
+}
+
+impl Curve {
+    fn segments(&self) -> Vec<Segment> {
+        (0..self.vertices.len())
+            .map(|i| {
+                let [control1, control2, end] = self.controls[i];
+                if self.corner[i] {
+                    Segment::Corner {
+                        vertex: self.vertices[i],
+                        end,
+                    }
+                } else {
+                    Segment::Curve {
+                        control1,
+                        control2,
+                        end,
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn end(&self, i: usize) -> Point {
+        self.controls[i % self.vertices.len()][2]
+    }
+
+    /// Tries to replace the segments after `i` up to and including `j` with
+    /// a single curve.
+    fn join(
+        &self,
+        i: usize,
+        j: usize,
+        tolerance: f64,
+        convexity: &[i8],
+        areas: &[f64],
+    ) -> Option<Joined> {
+        let m = self.vertices.len();
+        let vertex = |k: usize| self.vertices[k % m];
+        if i == j {
+            return None;
+        }
+        let i1 = (i + 1) % m;
+        let conv = convexity[i1];
+        if conv == 0 {
+            return None;
+        }
+        // The segments must all bend the same way by less than 180 degrees
+        // in total.
+        let d = distance(vertex(i), vertex(i1));
+        let mut k = i1;
+        while k != j {
+            let k1 = (k + 1) % m;
+            let k2 = (k + 2) % m;
+            if convexity[k1] != conv
+                || sign(cprod(vertex(i), vertex(i1), vertex(k1), vertex(k2))) != conv
+                || iprod(vertex(i), vertex(i1), vertex(k1), vertex(k2))
+                    < d * distance(vertex(k1), vertex(k2)) * -0.999_847_695_156
+            {
+                return None;
+            }
+            k = k1;
+        }
+
+        let p0 = self.end(i);
+        let p1 = vertex(i1);
+        let p2 = vertex(j);
+        let p3 = self.end(j);
+        // The area the curve must enclose to match the segments.
+        let mut area =
+            areas[j] - areas[i] - dpara(self.vertices[0], self.end(i), self.end(j)) / 2.0;
+        if i >= j {
+            area += areas[m];
+        }
+        let a1 = dpara(p0, p1, p2);
+        let a2 = dpara(p0, p1, p3);
+        let a3 = dpara(p0, p2, p3);
+        let a4 = a1 + a3 - a2;
+        if a2 - a1 == 0.0 {
+            return None;
+        }
+        let t = a3 / (a3 - a4);
+        let s = a2 / (a2 - a1);
+        let a = a2 * t / 2.0;
+        if a == 0.0 {
+            return None;
+        }
+        let alpha = 2.0 - (4.0 - area / a / 0.3).sqrt();
+        let controls = [interval(t * alpha, p0, p1), interval(s * alpha, p3, p2)];
+        let (q1, q2) = (controls[0], controls[1]);
+
+        // The curve must stay close to the polygon edges...
+        let mut penalty = 0.0;
+        let mut k = i1;
+        while k != j {
+            let k1 = (k + 1) % m;
+            let t = tangent(p0, q1, q2, p3, vertex(k), vertex(k1))?;
+            let point = bezier(t, p0, q1, q2, p3);
+            let d = distance(vertex(k), vertex(k1));
+            if d == 0.0 {
+                return None;
+            }
+            let d1 = dpara(vertex(k), vertex(k1), point) / d;
+            if d1.abs() > tolerance
+                || iprod(vertex(k), vertex(k1), vertex(k), point) < 0.0
+                || iprod(vertex(k1), vertex(k), vertex(k1), point) < 0.0
+            {
+                return None;
+            }
+            penalty += d1 * d1;
+            k = k1;
+        }
+        // ...and to the curves it replaces.
+        let mut k = i;
+        while k != j {
+            let k1 = (k + 1) % m;
+            let t = tangent(p0, q1, q2, p3, self.end(k), self.end(k1))?;
+            let point = bezier(t, p0, q1, q2, p3);
+            let d = distance(self.end(k), self.end(k1));
+            if d == 0.0 {
+                return None;
+            }
+            let mut d1 = dpara(self.end(k), self.end(k1), point) / d;
+            let mut d2 = dpara(self.end(k), self.end(k1), vertex(k1)) / d * 0.75 * self.alpha[k1];
+            if d2 < 0.0 {
+                d1 = -d1;
+                d2 = -d2;
+            }
+            if d1 < d2 - tolerance {
+                return None;
+            }
+            if d1 < d2 {
+                penalty += (d1 - d2) * (d1 - d2);
+            }
+            k = k1;
+        }
+        Some(Joined {
+            penalty,
+            controls,
+            alpha,
+            s,
+        })
+    }
+
+    /// Joins runs of segments into single curves, minimizing the number of
+    /// segments and then the penalty.
+    fn optimize(self, tolerance: f64) -> Curve {
+        let m = self.vertices.len();
+        let convexity: Vec<i8> = (0..m)
+            .map(|i| {
+                if self.corner[i] {
+                    0
+                } else {
+                    sign(dpara(
+                        self.vertices[(i + m - 1) % m],
+                        self.vertices[i],
+                        self.vertices[(i + 1) % m],
+                    ))
+                }
+            })
+            .collect();
+        // Cumulative area enclosed by the segments, relative to vertex 0.
+        let mut areas = vec![0.0; m + 1];
+        let mut area = 0.0;
+        let p0 = self.vertices[0];
+        for i in 0..m {
+            let i1 = (i + 1) % m;
+            if !self.corner[i1] {
+                let alpha = self.alpha[i1];
+                area += 0.3
+                    * alpha
+                    * (4.0 - alpha)
+                    * dpara(self.end(i), self.vertices[i1], self.end(i1))
+                    / 2.0;
+                area += dpara(p0, self.end(i), self.end(i1)) / 2.0;
+            }
+            areas[i + 1] = area;
+        }
+
+        let mut previous = vec![0; m + 1];
+        let mut penalties = vec![0.0; m + 1];
+        let mut lengths = vec![0; m + 1];
+        let mut joined = vec![None; m + 1];
+        for j in 1..=m {
+            previous[j] = j - 1;
+            penalties[j] = penalties[j - 1];
+            lengths[j] = lengths[j - 1] + 1;
+            for i in (0..j.saturating_sub(1)).rev() {
+                let Some(candidate) = self.join(i, j % m, tolerance, &convexity, &areas) else {
+                    break;
+                };
+                if lengths[j] > lengths[i] + 1
+                    || (lengths[j] == lengths[i] + 1
+                        && penalties[j] > penalties[i] + candidate.penalty)
+                {
+                    previous[j] = i;
+                    penalties[j] = penalties[i] + candidate.penalty;
+                    lengths[j] = lengths[i] + 1;
+                    joined[j] = Some(candidate);
+                }
+            }
+        }
+
+        let count = lengths[m];
+        let mut curve = Curve {
+            vertices: vec![(0.0, 0.0); count],
+            corner: vec![false; count],
+            controls: vec![[(0.0, 0.0); 3]; count],
+            alpha: vec![0.0; count],
+        };
+        let mut j = m;
+        for i in (0..count).rev() {
+            let source = j % m;
+            if previous[j] == j - 1 {
+                curve.vertices[i] = self.vertices[source];
+                curve.corner[i] = self.corner[source];
+                curve.controls[i] = self.controls[source];
+                curve.alpha[i] = self.alpha[source];
+            } else {
+                let candidate = joined[j].unwrap_or_default();
+                curve.vertices[i] = interval(candidate.s, self.end(source), self.vertices[source]);
+                curve.controls[i] = [
+                    candidate.controls[0],
+                    candidate.controls[1],
+                    self.end(source),
+                ];
+                curve.alpha[i] = candidate.alpha;
+            }
+            j = previous[j];
+        }
+        curve
+    }
+}
+
+fn sign(value: f64) -> i8 {
+    if value > 0.0 {
+        1
+    } else if value < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
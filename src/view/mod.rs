@@ -0,0 +1,270 @@
+#![allow(clippy::module_name_repetitions)]
+use std::ops::Deref;
+
+use image::{GenericImageView, Pixel};
+
+use crate::{BinaryImage, Bit};
+
+pub use ops::{AndView, NotView, OrView, XorView};
+pub use sub::{BinarySubView, BinarySubViewMut};
+pub(crate) use transform::transpose;
+pub use transform::TransformedView;
+
+mod ops;
+mod sub;
+mod transform;
+
+/// An image view with [`Bit`] pixels.
+///
+/// Provides lazy transformations and boolean expressions, and a
+/// [`materialize`](Self::materialize) that views backed by a [`BinaryImage`]
+/// implement with word-level operations.
+///
+/// Expressions own their operands, so borrow images through a cheap view
+/// such as [`transformed`](Self::transformed):
+///
+/// ```
+/// use binary_image::{BinaryImage, BitImageView};
+/// use image::GenericImageView;
+///
+/// let a = BinaryImage::from_raw(2, 1, &[1u8, 1]);
+/// let b = BinaryImage::from_raw(2, 1, &[0u8, 1]);
+/// let c = BinaryImage::from_raw(2, 1, &[0u8, 0]);
+///
+/// // a & !b | c
+/// let mask = a.transformed().and(b.inverted()).or(c.transformed());
+/// assert!(*mask.get_pixel(0, 0) && !*mask.get_pixel(1, 0));
+/// assert_eq!(mask.materialize().get_pixel(0, 0), mask.get_pixel(0, 0));
+/// ```
+pub trait BitImageView: GenericImageView<Pixel = Bit> {
+    /// Returns the packed image behind this view, if its pixels map onto it
+    /// one to one.
+    fn as_binary_image(&self) -> Option<&BinaryImage> {
+        None
+    }
+
+    /// Copies the view into a new [`BinaryImage`] holding exactly
+    /// `width * height` bits.
+    fn materialize(&self) -> BinaryImage {
+        let (width, height) = self.dimensions();
+        BinaryImage {
+            height,
+            width,
+            buffer: (0..height)
+                .flat_map(|y| (0..width).map(move |x| *self.get_pixel(x, y)))
+                .collect(),
+        }
+    }
+
+    /// Returns an untransformed lazy view, the starting point for chaining.
+    fn transformed(&self) -> TransformedView<'_, Self>
+    where
+        Self: Sized,
+    {
+        TransformedView::new(self)
+    }
+
+    /// Returns a lazy view with every pixel inverted.
+    fn inverted(&self) -> TransformedView<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.transformed().inverted()
+    }
+
+    /// Returns a lazy view flipped horizontally.
+    fn flipped_h(&self) -> TransformedView<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.transformed().flipped_h()
+    }
+
+    /// Returns a lazy view flipped vertically.
+    fn flipped_v(&self) -> TransformedView<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.transformed().flipped_v()
+    }
+
+    /// Returns a lazy view rotated 90 degrees clockwise.
+    fn rotated90(&self) -> TransformedView<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.transformed().rotated90()
+    }
+
+    /// Returns a lazy view rotated 180 degrees.
+    fn rotated180(&self) -> TransformedView<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.transformed().rotated180()
+    }
+
+    /// Returns a lazy view rotated 270 degrees clockwise.
+    fn rotated270(&self) -> TransformedView<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.transformed().rotated270()
+    }
+
+    /// Returns a lazy view with the axes swapped.
+    fn transposed(&self) -> TransformedView<'_, Self>
+    where
+        Self: Sized,
+    {
+        self.transformed().transposed()
+    }
+
+    /// Returns a lazy pixel-wise AND with `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ.
+    fn and<B: BitImageView>(self, other: B) -> AndView<Self, B>
+    where
+        Self: Sized,
+    {
+        AndView::new(self, other)
+    }
+
+    /// Returns a lazy pixel-wise OR with `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ.
+    fn or<B: BitImageView>(self, other: B) -> OrView<Self, B>
+    where
+        Self: Sized,
+    {
+        OrView::new(self, other)
+    }
+
+    /// Returns a lazy pixel-wise XOR with `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ.
+    fn xor<B: BitImageView>(self, other: B) -> XorView<Self, B>
+    where
+        Self: Sized,
+    {
+        XorView::new(self, other)
+    }
+
+    /// Returns a lazy pixel-wise NOT that owns this view.
+    fn not(self) -> NotView<Self>
+    where
+        Self: Sized,
+    {
+        NotView::new(self)
+    }
+}
+
+impl BitImageView for BinaryImage {
+    fn as_binary_image(&self) -> Option<&BinaryImage> {
+        Some(self)
+    }
+
+    fn materialize(&self) -> BinaryImage {
+        let mut out = self.clone();
+        out.buffer
+            .truncate(self.width as usize * self.height as usize);
+        out
+    }
+}
+
+impl<I, P> BitImageView for BinaryView<'_, I>
+where
+    I: GenericImageView<Pixel = P>,
+    P: Pixel,
+    crate::Bit: From<P>,
+{
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum BinaryView<'a, I: GenericImageView> {
+    Ref(&'a I),
+    Image(I),
+}
+
+impl<I, P> GenericImageView for BinaryView<'_, I>
+where
+    I: GenericImageView<Pixel = P>,
+    P: Pixel,
+    crate::Bit: From<P>,
+{
+    type Pixel = crate::Bit;
+    #[inline]
+    unsafe fn unsafe_get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        crate::Bit::from(self.deref().unsafe_get_pixel(x, y))
+    }
+    #[inline]
+    fn get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        debug_assert!(self.deref().in_bounds(x, y), "Pixel out of bounds");
+        unsafe { self.unsafe_get_pixel(x, y) }
+    }
+    #[inline]
+    fn dimensions(&self) -> (u32, u32) {
+        self.deref().dimensions()
+    }
+    #[inline]
+    fn height(&self) -> u32 {
+        self.deref().height()
+    }
+    #[inline]
+    fn width(&self) -> u32 {
+        self.deref().width()
+    }
+}
+
+impl<I, P> BinaryView<'_, I>
+where
+    I: GenericImageView<Pixel = P>,
+    P: Pixel,
+    crate::Bit: From<P>,
+{
+    /// Returns a read-only view of a region of the image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region does not fit inside the image.
+    #[must_use]
+    pub fn sub_view(&self, x: u32, y: u32, width: u32, height: u32) -> BinarySubView<'_, Self> {
+        BinarySubView::new(self, x, y, width, height)
+    }
+}
+
+impl<I, P> Deref for BinaryView<'_, I>
+where
+    I: GenericImageView<Pixel = P>,
+    P: Pixel,
+    crate::Bit: From<P>,
+{
+    type Target = I;
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Ref(image) => image,
+            Self::Image(image) => image,
+        }
+    }
+}
+
+impl<'a, I, P> From<BinaryView<'a, I>> for crate::BinaryImage
+where
+    I: GenericImageView<Pixel = P>,
+    P: Pixel,
+    crate::Bit: From<P>,
+{
+    fn from(view: BinaryView<'a, I>) -> BinaryImage {
+        BinaryImage {
+            height: view.height(),
+            width: view.width(),
+            buffer: view.pixels().map(|(_, _, pixel)| *pixel).collect(),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use image::GenericImageView;
+
+use super::BitImageView;
+use crate::{BinaryImage, Bit};
+
+macro_rules! binary_op_view {
+    ($(#[$attr:meta])* $name:ident, $op:tt, $method:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name<A, B> {
+            a: A,
+            b: B,
+        }
+
+        impl<A: BitImageView, B: BitImageView> $name<A, B> {
+            /// Combines two views of equal dimensions.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the dimensions differ.
+            #[must_use]
+            pub fn new(a: A, b: B) -> Self {
+                assert_eq!(a.dimensions(), b.dimensions(), "Dimensions mismatch");
+                Self { a, b }
+            }
+
+            /// The operands of the expression.
+            #[must_use]
+            pub fn operands(&self) -> (&A, &B) {
+                (&self.a, &self.b)
+            }
+        }
+
+        impl<A: BitImageView, B: BitImageView> GenericImageView for $name<A, B> {
+            type Pixel = Bit;
+            #[inline]
+            unsafe fn unsafe_get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+                self.a.unsafe_get_pixel(x, y) $op self.b.unsafe_get_pixel(x, y)
+            }
+            #[inline]
+            fn get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+                assert!(self.in_bounds(x, y), "Pixel out of bounds");
+                unsafe { self.unsafe_get_pixel(x, y) }
+            }
+            #[inline]
+            fn dimensions(&self) -> (u32, u32) {
+                self.a.dimensions()
+            }
+            #[inline]
+            fn height(&self) -> u32 {
+                self.a.height()
+            }
+            #[inline]
+            fn width(&self) -> u32 {
+                self.a.width()
+            }
+        }
+
+        impl<A: BitImageView, B: BitImageView> BitImageView for $name<A, B> {
+            fn materialize(&self) -> BinaryImage {
+                let mut out = self.a.materialize();
+                out.buffer.$method(&self.b.materialize().buffer);
+                out
+            }
+        }
+    };
+}
+
+binary_op_view!(
+    /// A lazy pixel-wise AND of two bit views.
+    AndView, &, and
+);
+binary_op_view!(
+    /// A lazy pixel-wise OR of two bit views.
+    OrView, |, or
+);
+binary_op_view!(
+    /// A lazy pixel-wise XOR of two bit views.
+    XorView, ^, xor
+);
+
+/// A lazy pixel-wise NOT of a bit view.
+///
+/// Unlike [`BitImageView::inverted`] this owns its operand, so it can wrap
+/// other expressions.
+#[derive(Debug, Clone, Copy)]
+pub struct NotView<A> {
+    a: A,
+}
+
+impl<A: BitImageView> NotView<A> {
+    #[must_use]
+    pub fn new(a: A) -> Self {
+        Self { a }
+    }
+
+    /// The operand of the expression.
+    #[must_use]
+    pub fn operand(&self) -> &A {
+        &self.a
+    }
+}
+
+impl<A: BitImageView> GenericImageView for NotView<A> {
+    type Pixel = Bit;
+    #[inline]
+    unsafe fn unsafe_get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        !self.a.unsafe_get_pixel(x, y)
+    }
+    #[inline]
+    fn get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        assert!(self.in_bounds(x, y), "Pixel out of bounds");
+        unsafe { self.unsafe_get_pixel(x, y) }
+    }
+    #[inline]
+    fn dimensions(&self) -> (u32, u32) {
+        self.a.dimensions()
+    }
+    #[inline]
+    fn height(&self) -> u32 {
+        self.a.height()
+    }
+    #[inline]
+    fn width(&self) -> u32 {
+        self.a.width()
+    }
+}
+
+impl<A: BitImageView> BitImageView for NotView<A> {
+    fn materialize(&self) -> BinaryImage {
+        let mut out = self.a.materialize();
+        out.buffer.negate();
+        out
+    }
+}
@@ -0,0 +1,334 @@
+use image::{GenericImage, GenericImageView};
+
+use super::BitImageView;
+use crate::{bits, BinaryImage, Bit};
+
+/// A read-only rectangular region of a bit image.
+///
+/// Unlike [`image::SubImage`] this implements [`GenericImageView`] directly,
+/// and converting a region of a [`BinaryImage`] back into an image copies
+/// whole words of the packed storage.
+#[derive(Debug)]
+pub struct BinarySubView<'a, I: GenericImageView<Pixel = Bit>> {
+    image: &'a I,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// A mutable rectangular region of a bit image.
+#[derive(Debug)]
+pub struct BinarySubViewMut<'a, I: GenericImage<Pixel = Bit>> {
+    image: &'a mut I,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+fn assert_region<I: GenericImageView>(image: &I, x: u32, y: u32, width: u32, height: u32) {
+    assert!(
+        u64::from(x) + u64::from(width) <= u64::from(image.width())
+            && u64::from(y) + u64::from(height) <= u64::from(image.height()),
+        "Sub view out of bounds"
+    );
+}
+
+impl<'a, I: GenericImageView<Pixel = Bit>> BinarySubView<'a, I> {
+    /// Creates a view of the given region of `image`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region does not fit inside `image`.
+    #[must_use]
+    pub fn new(image: &'a I, x: u32, y: u32, width: u32, height: u32) -> Self {
+        assert_region(image, x, y, width, height);
+        Self {
+            image,
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns a view of a region of this view, relative to its top-left corner.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region does not fit inside this view.
+    #[must_use]
+    pub fn sub_view(&self, x: u32, y: u32, width: u32, height: u32) -> BinarySubView<'a, I> {
+        assert_region(self, x, y, width, height);
+        BinarySubView {
+            image: self.image,
+            x: self.x + x,
+            y: self.y + y,
+            width,
+            height,
+        }
+    }
+
+    /// Position of the region inside the underlying image.
+    #[inline]
+    #[must_use]
+    pub fn offsets(&self) -> (u32, u32) {
+        (self.x, self.y)
+    }
+
+    /// The underlying image.
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &'a I {
+        self.image
+    }
+}
+
+impl<I: GenericImageView<Pixel = Bit>> Clone for BinarySubView<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I: GenericImageView<Pixel = Bit>> Copy for BinarySubView<'_, I> {}
+
+impl<I: GenericImageView<Pixel = Bit>> GenericImageView for BinarySubView<'_, I> {
+    type Pixel = Bit;
+    #[inline]
+    unsafe fn unsafe_get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        self.image.unsafe_get_pixel(self.x + x, self.y + y)
+    }
+    #[inline]
+    fn get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        assert!(self.in_bounds(x, y), "Pixel out of bounds");
+        unsafe { self.unsafe_get_pixel(x, y) }
+    }
+    #[inline]
+    fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+    #[inline]
+    fn height(&self) -> u32 {
+        self.height
+    }
+    #[inline]
+    fn width(&self) -> u32 {
+        self.width
+    }
+}
+
+impl BinarySubView<'_, BinaryImage> {
+    /// Copies the region into a new image.
+    #[must_use]
+    pub fn to_image(&self) -> BinaryImage {
+        self.image.crop(self.x, self.y, self.width, self.height)
+    }
+}
+
+impl<'a, I: GenericImage<Pixel = Bit>> BinarySubViewMut<'a, I> {
+    /// Creates a mutable view of the given region of `image`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region does not fit inside `image`.
+    #[must_use]
+    pub fn new(image: &'a mut I, x: u32, y: u32, width: u32, height: u32) -> Self {
+        assert_region(image, x, y, width, height);
+        Self {
+            image,
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns a read-only view of a region of this view.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region does not fit inside this view.
+    #[must_use]
+    pub fn sub_view(&self, x: u32, y: u32, width: u32, height: u32) -> BinarySubView<'_, I> {
+        assert_region(self, x, y, width, height);
+        BinarySubView {
+            image: &*self.image,
+            x: self.x + x,
+            y: self.y + y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns a mutable view of a region of this view.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region does not fit inside this view.
+    #[must_use]
+    pub fn sub_view_mut(
+        &mut self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> BinarySubViewMut<'_, I> {
+        assert_region(self, x, y, width, height);
+        BinarySubViewMut {
+            image: &mut *self.image,
+            x: self.x + x,
+            y: self.y + y,
+            width,
+            height,
+        }
+    }
+
+    /// Position of the region inside the underlying image.
+    #[inline]
+    #[must_use]
+    pub fn offsets(&self) -> (u32, u32) {
+        (self.x, self.y)
+    }
+}
+
+impl<I: GenericImage<Pixel = Bit>> GenericImageView for BinarySubViewMut<'_, I> {
+    type Pixel = Bit;
+    #[inline]
+    unsafe fn unsafe_get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        self.image.unsafe_get_pixel(self.x + x, self.y + y)
+    }
+    #[inline]
+    fn get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        assert!(self.in_bounds(x, y), "Pixel out of bounds");
+        unsafe { self.unsafe_get_pixel(x, y) }
+    }
+    #[inline]
+    fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+    #[inline]
+    fn height(&self) -> u32 {
+        self.height
+    }
+    #[inline]
+    fn width(&self) -> u32 {
+        self.width
+    }
+}
+
+impl<I: GenericImage<Pixel = Bit>> GenericImage for BinarySubViewMut<'_, I> {
+    #[inline]
+    unsafe fn unsafe_put_pixel(&mut self, x: u32, y: u32, pixel: Self::Pixel) {
+        self.image.unsafe_put_pixel(self.x + x, self.y + y, pixel);
+    }
+    #[inline]
+    fn put_pixel(&mut self, x: u32, y: u32, pixel: Self::Pixel) {
+        assert!(self.in_bounds(x, y), "Pixel out of bounds");
+        unsafe { self.unsafe_put_pixel(x, y, pixel) }
+    }
+    fn blend_pixel(&mut self, x: u32, y: u32, other: Self::Pixel) {
+        let mut pixel = self.get_pixel(x, y);
+        image::Pixel::blend(&mut pixel, &other);
+        self.put_pixel(x, y, pixel);
+    }
+    /// # Panics
+    ///
+    /// Always panics, as pixels are packed bits that cannot be borrowed
+    /// mutably; use [`put_pixel`](GenericImage::put_pixel) instead.
+    fn get_pixel_mut(&mut self, _: u32, _: u32) -> &mut Self::Pixel {
+        panic!("Bit pixels cannot be borrowed mutably, use put_pixel instead")
+    }
+}
+
+impl BinarySubViewMut<'_, BinaryImage> {
+    /// Copies the region into a new image.
+    #[must_use]
+    pub fn to_image(&self) -> BinaryImage {
+        self.image.crop(self.x, self.y, self.width, self.height)
+    }
+
+    /// Sets every pixel of the region to `value`.
+    pub fn fill(&mut self, value: Bit) {
+        for row in self.y..self.y + self.height {
+            let offset = self.image.row_offset(row) + self.x as usize;
+            bits::fill(&mut self.image.buffer, offset, self.width as usize, *value);
+        }
+    }
+
+    /// Copies `image` into the region, which must have the same dimensions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ.
+    pub fn copy_from_image(&mut self, image: &BinaryImage) {
+        assert_eq!(image.dimensions(), self.dimensions(), "Dimensions mismatch");
+        for row in 0..self.height {
+            let dst = self.image.row_offset(self.y + row) + self.x as usize;
+            bits::copy(
+                &image.buffer,
+                image.row_offset(row),
+                &mut self.image.buffer,
+                dst,
+                self.width as usize,
+            );
+        }
+    }
+}
+
+impl<I: BitImageView> BitImageView for BinarySubView<'_, I> {
+    fn materialize(&self) -> BinaryImage {
+        match self.image.as_binary_image() {
+            Some(image) => image.crop(self.x, self.y, self.width, self.height),
+            None => BinaryImage {
+                height: self.height,
+                width: self.width,
+                buffer: self.pixels().map(|(_, _, pixel)| *pixel).collect(),
+            },
+        }
+    }
+}
+
+impl<I: GenericImage<Pixel = Bit> + BitImageView> BitImageView for BinarySubViewMut<'_, I> {
+    fn materialize(&self) -> BinaryImage {
+        self.sub_view(0, 0, self.width, self.height).materialize()
+    }
+}
+
+impl<'a, I: GenericImageView<Pixel = Bit>> From<BinarySubView<'a, I>> for BinaryImage {
+    fn from(view: BinarySubView<'a, I>) -> BinaryImage {
+        BinaryImage {
+            height: view.height(),
+            width: view.width(),
+            buffer: view.pixels().map(|(_, _, pixel)| *pixel).collect(),
+        }
+    }
+}
+
+impl BinaryImage {
+    /// Returns a read-only view of a region of the image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region does not fit inside the image.
+    #[must_use]
+    pub fn sub_view(&self, x: u32, y: u32, width: u32, height: u32) -> BinarySubView<'_, Self> {
+        BinarySubView::new(self, x, y, width, height)
+    }
+
+    /// Returns a mutable view of a region of the image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region does not fit inside the image.
+    #[must_use]
+    pub fn sub_view_mut(
+        &mut self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> BinarySubViewMut<'_, Self> {
+        BinarySubViewMut::new(self, x, y, width, height)
+    }
+}
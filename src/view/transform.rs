@@ -0,0 +1,198 @@
+use image::GenericImageView;
+
+use super::BitImageView;
+use crate::{bits, BinaryImage, Bit};
+
+/// A lazy flip, rotation, transposition and/or inversion of a bit image.
+///
+/// Transformations compose by updating the view in place instead of nesting,
+/// so `image.rotated90().flipped_h().inverted()` is still a single lookup per
+/// pixel. Create one with the methods of [`BitImageView`].
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug)]
+pub struct TransformedView<'a, I: BitImageView> {
+    image: &'a I,
+    // Output coordinates are flipped first, then swapped if `transpose` is set.
+    flip_h: bool,
+    flip_v: bool,
+    transpose: bool,
+    invert: bool,
+}
+
+impl<'a, I: BitImageView> TransformedView<'a, I> {
+    /// Creates an untransformed view of `image`.
+    #[must_use]
+    pub fn new(image: &'a I) -> Self {
+        Self {
+            image,
+            flip_h: false,
+            flip_v: false,
+            transpose: false,
+            invert: false,
+        }
+    }
+
+    /// Inverts every pixel.
+    #[must_use]
+    pub fn inverted(mut self) -> Self {
+        self.invert = !self.invert;
+        self
+    }
+
+    /// Flips the view horizontally.
+    #[must_use]
+    pub fn flipped_h(mut self) -> Self {
+        self.flip_h = !self.flip_h;
+        self
+    }
+
+    /// Flips the view vertically.
+    #[must_use]
+    pub fn flipped_v(mut self) -> Self {
+        self.flip_v = !self.flip_v;
+        self
+    }
+
+    /// Swaps the axes of the view.
+    #[must_use]
+    pub fn transposed(mut self) -> Self {
+        std::mem::swap(&mut self.flip_h, &mut self.flip_v);
+        self.transpose = !self.transpose;
+        self
+    }
+
+    /// Rotates the view 90 degrees clockwise.
+    #[must_use]
+    pub fn rotated90(self) -> Self {
+        self.transposed().flipped_h()
+    }
+
+    /// Rotates the view 180 degrees.
+    #[must_use]
+    pub fn rotated180(self) -> Self {
+        self.flipped_h().flipped_v()
+    }
+
+    /// Rotates the view 270 degrees clockwise.
+    #[must_use]
+    pub fn rotated270(self) -> Self {
+        self.transposed().flipped_v()
+    }
+
+    /// The underlying image.
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &'a I {
+        self.image
+    }
+
+    #[inline]
+    fn source_coords(&self, x: u32, y: u32) -> (u32, u32) {
+        let (width, height) = self.dimensions();
+        let x = if self.flip_h { width - 1 - x } else { x };
+        let y = if self.flip_v { height - 1 - y } else { y };
+        if self.transpose {
+            (y, x)
+        } else {
+            (x, y)
+        }
+    }
+
+    fn materialize_packed(&self, image: &BinaryImage) -> BinaryImage {
+        let transposed;
+        let source = if self.transpose {
+            transposed = transpose(image);
+            &transposed
+        } else {
+            image
+        };
+        let (width, height) = source.dimensions();
+        let mut out = BinaryImage::new(width, height);
+        for y in 0..height {
+            let src = source.row_offset(if self.flip_v { height - 1 - y } else { y });
+            let dst = out.row_offset(y);
+            if self.flip_h {
+                bits::copy_reversed(&source.buffer, src, &mut out.buffer, dst, width as usize);
+            } else {
+                bits::copy(&source.buffer, src, &mut out.buffer, dst, width as usize);
+            }
+        }
+        if self.invert {
+            out.buffer.negate();
+        }
+        out
+    }
+}
+
+/// Transposes `image` 32x32 bits at a time.
+pub(crate) fn transpose(image: &BinaryImage) -> BinaryImage {
+    let (width, height) = image.dimensions();
+    let mut out = BinaryImage::new(height, width);
+    let mut block = [0; bits::WORD];
+    for by in (0..height as usize).step_by(bits::WORD) {
+        let rows = (height as usize - by).min(bits::WORD);
+        for bx in (0..width as usize).step_by(bits::WORD) {
+            let cols = (width as usize - bx).min(bits::WORD);
+            for (r, word) in block.iter_mut().enumerate() {
+                *word = if r < rows {
+                    bits::read(&image.buffer, (by + r) * width as usize + bx, cols)
+                } else {
+                    0
+                };
+            }
+            bits::transpose32(&mut block);
+            for (c, &word) in block.iter().enumerate().take(cols) {
+                bits::write(&mut out.buffer, (bx + c) * height as usize + by, rows, word);
+            }
+        }
+    }
+    out
+}
+
+impl<I: BitImageView> Clone for TransformedView<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I: BitImageView> Copy for TransformedView<'_, I> {}
+
+impl<I: BitImageView> GenericImageView for TransformedView<'_, I> {
+    type Pixel = Bit;
+    #[inline]
+    unsafe fn unsafe_get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        let (x, y) = self.source_coords(x, y);
+        Bit(self.image.unsafe_get_pixel(x, y).0 ^ self.invert)
+    }
+    #[inline]
+    fn get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        assert!(self.in_bounds(x, y), "Pixel out of bounds");
+        unsafe { self.unsafe_get_pixel(x, y) }
+    }
+    #[inline]
+    fn dimensions(&self) -> (u32, u32) {
+        let (width, height) = self.image.dimensions();
+        if self.transpose {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+    #[inline]
+    fn height(&self) -> u32 {
+        self.dimensions().1
+    }
+    #[inline]
+    fn width(&self) -> u32 {
+        self.dimensions().0
+    }
+}
+
+impl<I: BitImageView> BitImageView for TransformedView<'_, I> {
+    fn materialize(&self) -> BinaryImage {
+        match self.image.as_binary_image() {
+            Some(image) => self.materialize_packed(image),
+            None => self.materialize_packed(&self.image.materialize()),
+        }
+    }
+}